@@ -0,0 +1,24 @@
+//! Frame time with and without the texture cache.
+//!
+//! Run with `cargo bench --features bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use map_panels::bench::{FrameBench, ImageStrategy};
+
+/// One hero image plus a carousel's worth of cards.
+const IMAGES: usize = 13;
+
+fn frame_time(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_time");
+    for (name, strategy) in [
+        ("upload_every_frame", ImageStrategy::UploadEveryFrame),
+        ("texture_cache", ImageStrategy::TextureCache),
+    ] {
+        let mut bench = FrameBench::new(strategy, IMAGES);
+        group.bench_function(name, |b| b.iter(|| bench.run_frame()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, frame_time);
+criterion_main!(benches);
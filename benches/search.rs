@@ -0,0 +1,43 @@
+//! Search over 10,000 synthetic repositories, with and without the index.
+//!
+//! Run with `cargo bench --features bench --bench search`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use map_panels::bench::SearchBench;
+
+const REPOS: usize = 10_000;
+
+/// A common word, a rare one, a whole badge, a phrase and a miss.
+const QUERIES: &[&str] = &[
+    "map",
+    "shader-audio-97",
+    "machine learning",
+    "tile for",
+    "zzz",
+];
+
+fn search(c: &mut Criterion) {
+    let bench = SearchBench::new(REPOS);
+    c.bench_function("search/build_index", |b| b.iter(|| bench.build_index()));
+
+    let mut group = c.benchmark_group("search/query");
+    for query in QUERIES {
+        group.bench_with_input(BenchmarkId::new("indexed", query), query, |b, query| {
+            b.iter(|| bench.search_indexed(query))
+        });
+        group.bench_with_input(BenchmarkId::new("linear", query), query, |b, query| {
+            b.iter(|| bench.search_linear(query))
+        });
+    }
+    group.finish();
+
+    // Frames where neither the data nor the query changed reuse the cached view.
+    let mut bench = SearchBench::new(REPOS);
+    bench.filter_view("map");
+    c.bench_function("search/unchanged_frame", |b| {
+        b.iter(|| bench.filter_view("map"))
+    });
+}
+
+criterion_group!(benches, search);
+criterion_main!(benches);
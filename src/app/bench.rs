@@ -0,0 +1,223 @@
+//! Entry points for the benchmarks under `benches/`, only compiled with the
+//! `bench` feature.
+//!
+//! [`FrameBench`] draws a hero image plus a row of card images every frame,
+//! either uploading each texture anew like the hero used to, or going through
+//! [`TextureCache`]. [`SearchBench`] searches a large synthetic portfolio.
+
+use std::sync::Arc;
+
+use super::data::{
+    FilteredSections, Query, RepoSection, RepoSummary, SearchIndex, SectionMeta, Tag, TagRegistry,
+    TagSelection,
+};
+use super::settings::ResultOrder;
+use super::textures::{TextureCache, TextureState};
+
+const LOGO_BYTES: &[u8] = include_bytes!("../../assets/logo.webp");
+
+/// How images get onto the screen each frame.
+#[derive(Clone, Copy, Debug)]
+pub enum ImageStrategy {
+    /// Calls `Context::load_texture` with a clone of the decoded pixels every frame.
+    UploadEveryFrame,
+    /// Looks the textures up in a [`TextureCache`], uploading them only once.
+    TextureCache,
+}
+
+/// An egui context that renders a fixed page of images without a window.
+pub struct FrameBench {
+    ctx: egui::Context,
+    strategy: ImageStrategy,
+    uris: Vec<String>,
+    decoded: egui::ColorImage,
+    textures: TextureCache,
+}
+
+impl FrameBench {
+    /// Prepares a page with `images` images, all decoded and, for the cached
+    /// strategy, already uploaded so only steady-state frames are measured.
+    pub fn new(strategy: ImageStrategy, images: usize) -> Self {
+        let ctx = egui::Context::default();
+        egui_extras::install_image_loaders(&ctx);
+        let decoded =
+            egui_extras::image::load_image_bytes(LOGO_BYTES).expect("bundled logo should decode");
+        let mut bench = Self {
+            ctx,
+            strategy,
+            uris: (0..images)
+                .map(|index| format!("bytes://bench-image-{index}.webp"))
+                .collect(),
+            decoded,
+            textures: TextureCache::default(),
+        };
+        if let ImageStrategy::TextureCache = strategy {
+            bench.warm_up();
+        }
+        bench
+    }
+
+    /// Runs and tessellates one frame.
+    pub fn run_frame(&mut self) {
+        let Self {
+            ctx,
+            strategy,
+            uris,
+            decoded,
+            textures,
+        } = self;
+        let output = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for uri in uris.iter() {
+                        let texture = match strategy {
+                            ImageStrategy::UploadEveryFrame => {
+                                let handle = ctx.load_texture(
+                                    uri.as_str(),
+                                    decoded.clone(),
+                                    egui::TextureOptions::LINEAR,
+                                );
+                                egui::load::SizedTexture::from_handle(&handle)
+                            }
+                            ImageStrategy::TextureCache => match textures.texture(ctx, uri) {
+                                TextureState::Ready(texture) => texture,
+                                TextureState::Pending | TextureState::Failed => {
+                                    unreachable!("textures are uploaded during warm-up")
+                                }
+                            },
+                        };
+                        ui.add(
+                            egui::Image::from_texture(texture)
+                                .max_size(egui::vec2(160.0, 120.0))
+                                .corner_radius(10.0),
+                        );
+                    }
+                });
+            });
+        });
+        std::hint::black_box(ctx.tessellate(output.shapes, output.pixels_per_point));
+        textures.end_frame();
+    }
+
+    /// Polls the image loaders until every image is decoded and uploaded.
+    fn warm_up(&mut self) {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        for uri in &self.uris {
+            let bytes = egui::load::Bytes::Static(LOGO_BYTES);
+            self.ctx.include_bytes(uri.clone(), bytes);
+            loop {
+                match self.textures.texture(&self.ctx, uri) {
+                    TextureState::Ready(_) => break,
+                    TextureState::Failed => panic!("failed to load {uri}"),
+                    TextureState::Pending => {
+                        assert!(std::time::Instant::now() < deadline, "decoding timed out");
+                        std::thread::sleep(std::time::Duration::from_millis(5));
+                    }
+                }
+            }
+        }
+    }
+}
+
+const WORDS: &[&str] = &[
+    "async", "viewer", "parser", "tile", "map", "render", "cache", "http", "cli", "plugin",
+    "graph", "shader", "audio", "index", "search", "sync", "layout", "font", "image", "stream",
+];
+const BADGES: &[&str] = &[
+    "Rust",
+    "WebAssembly",
+    "egui",
+    "Python",
+    "C++",
+    "machine learning",
+    "TypeScript",
+    "Go",
+];
+
+/// A synthetic portfolio and its search index.
+pub struct SearchBench {
+    sections: Arc<[RepoSection]>,
+    index: SearchIndex,
+    view: FilteredSections,
+}
+
+impl SearchBench {
+    /// Generates `repos` repositories spread over sections of 500 with
+    /// deterministic names, descriptions and badges.
+    pub fn new(repos: usize) -> Self {
+        let word = |seed: usize| WORDS[seed % WORDS.len()];
+        let items: Vec<RepoSummary> = (0..repos)
+            .map(|i| RepoSummary {
+                name: format!("{}-{}-{i}", word(i), word(i / 7)),
+                description: format!(
+                    "A {} {} for {} {} projects",
+                    word(i * 3),
+                    word(i * 5 + 1),
+                    word(i * 11 + 2),
+                    word(i / 3),
+                ),
+                image_url: None,
+                repo_url: format!("https://example.com/repo-{i}"),
+                badges: Tag::split(&format!(
+                    "{}, {}",
+                    BADGES[i % BADGES.len()],
+                    BADGES[(i / 5) % BADGES.len()]
+                )),
+                updated_at: None,
+            })
+            .collect();
+        let sections: Arc<[RepoSection]> = items
+            .chunks(500)
+            .enumerate()
+            .map(|(index, items)| RepoSection {
+                name: format!("Section {index}"),
+                meta: SectionMeta::default(),
+                items: items.to_vec(),
+            })
+            .collect();
+        Self {
+            index: SearchIndex::build(&sections, &TagRegistry::default()),
+            sections,
+            view: FilteredSections::default(),
+        }
+    }
+
+    /// Builds a fresh index, as happens once per loaded snapshot.
+    pub fn build_index(&self) {
+        std::hint::black_box(SearchIndex::build(&self.sections, &TagRegistry::default()));
+    }
+
+    /// Number of matches for `query`, looked up in the index.
+    pub fn search_indexed(&self, query: &str) -> usize {
+        let query = Query::parse(query).expect("bench queries should parse");
+        self.index.search(&self.sections, &query).len()
+    }
+
+    /// Number of matches for `query`, lowercasing every field of every item
+    /// the way filtering worked before the index.
+    pub fn search_linear(&self, query: &str) -> usize {
+        let query_lower = query.to_lowercase();
+        self.sections
+            .iter()
+            .flat_map(|section| &section.items)
+            .filter(|repo| {
+                repo.name.to_lowercase().contains(&query_lower)
+                    || repo.description.to_lowercase().contains(&query_lower)
+                    || repo
+                        .badges
+                        .iter()
+                        .any(|badge| badge.label().to_lowercase().contains(&query_lower))
+            })
+            .count()
+    }
+
+    /// Updates the cached view for `query`; free when neither data nor query changed.
+    pub fn filter_view(&mut self, query: &str) -> bool {
+        self.view.update(
+            &self.sections,
+            query,
+            &TagSelection::default(),
+            ResultOrder::Relevance,
+        )
+    }
+}
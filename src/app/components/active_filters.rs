@@ -0,0 +1,64 @@
+use crate::app::data::{TagCount, TagRegistry, TagSelection};
+use crate::app::theme::text;
+
+use super::tag_chip::TagChip;
+
+/// Row of the selected tag filters under the navigation bar; clicking a chip removes it.
+pub(crate) struct ActiveFilters<'a> {
+    selection: &'a mut TagSelection,
+    /// Used to show tags as written in the data rather than folded.
+    facets: &'a [TagCount],
+    tags: &'a TagRegistry,
+}
+
+impl<'a> ActiveFilters<'a> {
+    pub(crate) fn new(
+        selection: &'a mut TagSelection,
+        facets: &'a [TagCount],
+        tags: &'a TagRegistry,
+    ) -> Self {
+        Self {
+            selection,
+            facets,
+            tags,
+        }
+    }
+
+    pub(crate) fn show(self, ui: &mut egui::Ui) {
+        let ActiveFilters {
+            selection,
+            facets,
+            tags,
+        } = self;
+        if selection.is_empty() {
+            return;
+        }
+
+        let mut removed = None;
+        let mut clear = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 6.0;
+            let joined = format!("タグ（{}）:", selection.mode.label());
+            ui.label(egui::RichText::new(joined).small().color(text::MUTED));
+            for key in selection.keys() {
+                let label = facets
+                    .iter()
+                    .find(|facet| facet.key == key)
+                    .map_or(key, |facet| facet.label.as_str());
+                let chip = TagChip::new(label).style(tags.style(key)).removable();
+                if chip.show(ui).clicked() {
+                    removed = Some(key.to_owned());
+                }
+            }
+            if selection.len() > 1 && ui.small_button("すべて解除").clicked() {
+                clear = true;
+            }
+        });
+
+        if clear {
+            selection.clear();
+        } else if let Some(key) = removed {
+            selection.toggle(&key);
+        }
+    }
+}
@@ -1,22 +1,43 @@
-use crate::app::data::RepoSection;
+use crate::app::data::{SectionView, TagRegistry};
 use crate::app::layout::ResponsiveLayout;
+use crate::app::textures::TextureCache;
 use crate::app::theme::text;
 
 use super::repo_card::RepoCard;
 
-/// Displays each repository section as a horizontal scrolling carousel.
+/// Displays the matching items of a repository section as a horizontal scrolling carousel.
 pub(crate) struct RepoCarousel<'a> {
-    section: &'a RepoSection,
+    section: SectionView<'a>,
+    tags: &'a TagRegistry,
+    textures: &'a mut TextureCache,
     layout: ResponsiveLayout,
 }
 
 impl<'a> RepoCarousel<'a> {
-    pub(crate) fn new(section: &'a RepoSection, layout: ResponsiveLayout) -> Self {
-        Self { section, layout }
+    pub(crate) fn new(
+        section: SectionView<'a>,
+        tags: &'a TagRegistry,
+        textures: &'a mut TextureCache,
+        layout: ResponsiveLayout,
+    ) -> Self {
+        Self {
+            section,
+            tags,
+            textures,
+            layout,
+        }
     }
 
-    pub(crate) fn show(self, ui: &mut egui::Ui) {
-        let RepoCarousel { section, layout } = self;
+    /// Returns the tag clicked on one of the cards, if any.
+    pub(crate) fn show(self, ui: &mut egui::Ui) -> Option<String> {
+        let RepoCarousel {
+            section: view,
+            tags,
+            textures,
+            layout,
+        } = self;
+        let section = view.section;
+        let mut clicked_tag = None;
         ui.add_space(8.0);
         ui.heading(
             egui::RichText::new(&section.name)
@@ -28,17 +49,40 @@ impl<'a> RepoCarousel<'a> {
             .animated(true)
             .auto_shrink([false, true])
             .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysHidden)
-            .show(ui, |ui| {
+            .show_viewport(ui, |ui, viewport| {
+                // Cards all have the size of the last one drawn, so cards outside the
+                // visible range only reserve their space instead of being laid out.
+                let card_size_id = egui::Id::new("repo-card-size");
+                let card_size = ui.data(|data| data.get_temp::<egui::Vec2>(card_size_id));
                 ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
-                    for repo in &section.items {
-                        ui.push_id((&section.name, &repo.name), |ui| {
-                            RepoCard::new(repo, layout).show(ui);
-                        });
+                    let origin = ui.min_rect().left();
+                    for (repo, hit) in view.items() {
+                        let left = ui.cursor().left() - origin;
+                        if let Some(size) = card_size {
+                            if left + size.x < viewport.left() || left > viewport.right() {
+                                ui.allocate_space(size);
+                                ui.add_space(12.0);
+                                continue;
+                            }
+                        }
+                        let size = ui
+                            .push_id((&section.name, &repo.name), |ui| {
+                                if let Some(tag) =
+                                    RepoCard::new(repo, hit, tags, textures, layout).show(ui)
+                                {
+                                    clicked_tag = Some(tag);
+                                }
+                            })
+                            .response
+                            .rect
+                            .size();
+                        ui.data_mut(|data| data.insert_temp(card_size_id, size));
                         ui.add_space(12.0);
                     }
                 });
             });
         // Keep the scroll bar from overlapping the card content.
         ui.add_space(8.0);
+        clicked_tag
     }
 }
@@ -0,0 +1,120 @@
+use crate::app::data::{
+    ExcerptLine, ParseDiagnostic, PortfolioError, PortfolioErrorKind, TextLocation,
+};
+use crate::app::theme::{background, stroke, text};
+
+/// Explains a portfolio parse error with the offending lines of the document.
+pub(crate) struct ParseDiagnostics<'a> {
+    error: &'a PortfolioError,
+}
+
+impl<'a> ParseDiagnostics<'a> {
+    pub(crate) fn new(error: &'a PortfolioError) -> Self {
+        Self { error }
+    }
+
+    /// Draws nothing unless the error is a parse error.
+    pub(crate) fn show(self, ui: &mut egui::Ui) {
+        let PortfolioErrorKind::Parse(diagnostic) = &self.error.kind else {
+            return;
+        };
+        let ParseDiagnostic {
+            format,
+            message,
+            location,
+            path,
+            excerpt,
+        } = diagnostic.as_ref();
+
+        egui::Frame::default()
+            .fill(background::CARD)
+            .stroke(egui::Stroke::new(1.0_f32, stroke::CARD))
+            .corner_radius(10.0)
+            .inner_margin(egui::Margin::symmetric(14, 10))
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing.y = 4.0;
+                ui.label(
+                    egui::RichText::new(format!("{format} 診断"))
+                        .strong()
+                        .color(text::PRIMARY),
+                );
+                detail_row(ui, "取得元", &self.error.source.to_string());
+                if let Some(path) = path {
+                    detail_row(ui, "パス", path);
+                }
+                if let Some(location) = location {
+                    detail_row(
+                        ui,
+                        "位置",
+                        &format!("{}行 {}列", location.line, location.column),
+                    );
+                }
+                detail_row(ui, "内容", message);
+
+                if !excerpt.is_empty() {
+                    ui.add_space(6.0);
+                    egui::Frame::default()
+                        .fill(background::CODE)
+                        .corner_radius(6.0)
+                        .inner_margin(egui::Margin::symmetric(10, 8))
+                        .show(ui, |ui| {
+                            ui.spacing_mut().item_spacing.y = 0.0;
+                            for line in excerpt {
+                                ui.label(excerpt_line(line, *location));
+                            }
+                        });
+                }
+            });
+    }
+}
+
+fn detail_row(ui: &mut egui::Ui, label: &str, value: &str) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(egui::RichText::new(label).small().color(text::SECONDARY));
+        ui.label(egui::RichText::new(value).monospace().color(text::PRIMARY));
+    });
+}
+
+/// Formats one numbered line, highlighting it (and the error column) when it is the offending one.
+pub(crate) fn excerpt_line(
+    line: &ExcerptLine,
+    location: Option<TextLocation>,
+) -> egui::text::LayoutJob {
+    let font = egui::FontId::monospace(12.0);
+    let format = |color, background| egui::TextFormat {
+        font_id: font.clone(),
+        color,
+        background,
+        ..Default::default()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    job.append(
+        &format!("{:>4} │ ", line.number),
+        0.0,
+        format(text::SECONDARY, egui::Color32::TRANSPARENT),
+    );
+
+    let Some(location) = location.filter(|location| location.line == line.number) else {
+        job.append(
+            &line.text,
+            0.0,
+            format(text::SECONDARY, egui::Color32::TRANSPARENT),
+        );
+        return job;
+    };
+
+    // 列は文字単位（1 始まり）。行末を指す場合は空白を強調する
+    let column = location.column.saturating_sub(1);
+    let before: String = line.text.chars().take(column).collect();
+    let at: String = line.text.chars().skip(column).take(1).collect();
+    let after: String = line.text.chars().skip(column + 1).collect();
+    let line_format = format(text::PRIMARY, background::ERROR_LINE);
+    job.append(&before, 0.0, line_format.clone());
+    job.append(
+        if at.is_empty() { " " } else { &at },
+        0.0,
+        format(text::PRIMARY, background::ERROR_MARK),
+    );
+    job.append(&after, 0.0, line_format);
+    job
+}
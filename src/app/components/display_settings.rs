@@ -0,0 +1,61 @@
+use crate::app::settings::{DisplaySettings, RefreshInterval, ResultOrder};
+use crate::app::theme::{background, stroke, text};
+
+/// Floating window opened from "表示設定" in the settings menu.
+pub(crate) struct DisplaySettingsWindow<'a> {
+    open: &'a mut bool,
+    settings: &'a mut DisplaySettings,
+}
+
+impl<'a> DisplaySettingsWindow<'a> {
+    pub(crate) fn new(open: &'a mut bool, settings: &'a mut DisplaySettings) -> Self {
+        Self { open, settings }
+    }
+
+    pub(crate) fn show(self, ctx: &egui::Context) {
+        let settings = self.settings;
+        egui::Window::new(egui::RichText::new("表示設定").color(text::PRIMARY))
+            .id(egui::Id::new("display-settings"))
+            .open(self.open)
+            .collapsible(false)
+            .resizable(false)
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .fill(background::CARD)
+                    .stroke(egui::Stroke::new(1.0_f32, stroke::CARD))
+                    .corner_radius(10.0)
+                    .inner_margin(egui::Margin::symmetric(16, 12)),
+            )
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new("リポジトリデータの自動更新").color(text::PRIMARY));
+                egui::ComboBox::from_id_salt("auto-refresh-interval")
+                    .selected_text(settings.auto_refresh.label())
+                    .show_ui(ui, |ui| {
+                        for interval in RefreshInterval::ALL {
+                            ui.selectable_value(
+                                &mut settings.auto_refresh,
+                                interval,
+                                interval.label(),
+                            );
+                        }
+                    });
+                ui.label(
+                    egui::RichText::new(
+                        "内容が変わったときだけ表示を更新します。ウィンドウの最小化中とブラウザのタブが非表示の間は停止します",
+                    )
+                    .small()
+                    .color(text::SECONDARY),
+                );
+
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new("検索結果の並び順").color(text::PRIMARY));
+                egui::ComboBox::from_id_salt("result-order")
+                    .selected_text(settings.result_order.label())
+                    .show_ui(ui, |ui| {
+                        for order in ResultOrder::ALL {
+                            ui.selectable_value(&mut settings.result_order, order, order.label());
+                        }
+                    });
+            });
+    }
+}
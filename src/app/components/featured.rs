@@ -1,25 +1,80 @@
-use crate::app::data::FeaturedRepo;
+use crate::app::data::{FeaturedRepo, TagRegistry};
 use crate::app::layout::ResponsiveLayout;
+use crate::app::textures::{TextureCache, TextureState};
 use crate::app::theme::{background, text};
 
 use super::tag_chip::TagChip;
-use image::load_from_memory;
-use std::sync::OnceLock;
+use std::time::Duration;
 
-/// Draws the hero section that highlights the featured repository.
+/// Bundled image shown when a featured repository has no `image_url` or it fails to load.
+const LOGO_URI: &str = "bytes://featured-logo.webp";
+const LOGO_BYTES: &[u8] = include_bytes!("../../../assets/logo.webp");
+
+/// Seconds each featured repository stays on screen before rotating to the next.
+const ROTATE_INTERVAL: f64 = 8.0;
+
+/// Which featured repository is shown, kept in egui's temporary memory.
+#[derive(Clone, Copy, Default)]
+struct Rotation {
+    index: usize,
+    shown_since: Option<f64>,
+}
+
+/// Draws the hero section that highlights the featured repositories.
+///
+/// With more than one repository it rotates through them on a timer (paused
+/// while hovered) and shows previous/next buttons with indicator dots.
 pub(crate) struct FeaturedSection<'a> {
-    featured: &'a FeaturedRepo,
+    featured: &'a [FeaturedRepo],
+    tags: &'a TagRegistry,
+    textures: &'a mut TextureCache,
     layout: ResponsiveLayout,
 }
 
 impl<'a> FeaturedSection<'a> {
-    pub(crate) fn new(featured: &'a FeaturedRepo, layout: ResponsiveLayout) -> Self {
-        Self { featured, layout }
+    pub(crate) fn new(
+        featured: &'a [FeaturedRepo],
+        tags: &'a TagRegistry,
+        textures: &'a mut TextureCache,
+        layout: ResponsiveLayout,
+    ) -> Self {
+        Self {
+            featured,
+            tags,
+            textures,
+            layout,
+        }
     }
 
-    pub(crate) fn show(self, ui: &mut egui::Ui) {
-        let FeaturedSection { featured, layout } = self;
-        egui::Frame::default()
+    /// Returns the tag clicked in the hero, if any.
+    pub(crate) fn show(self, ui: &mut egui::Ui) -> Option<String> {
+        let FeaturedSection {
+            featured,
+            tags,
+            textures,
+            layout,
+        } = self;
+        let count = featured.len();
+        if count == 0 {
+            return None;
+        }
+
+        let id = egui::Id::new("featured-rotation");
+        let now = ui.input(|i| i.time);
+        let mut rotation = ui
+            .ctx()
+            .data(|data| data.get_temp::<Rotation>(id))
+            .unwrap_or_default();
+        rotation.index %= count;
+        let shown_since = *rotation.shown_since.get_or_insert(now);
+        if count > 1 && now - shown_since >= ROTATE_INTERVAL {
+            rotation.index = (rotation.index + 1) % count;
+            rotation.shown_since = Some(now);
+        }
+        let current = &featured[rotation.index];
+        let mut clicked_tag = None;
+
+        let response = egui::Frame::default()
             .fill(background::FEATURED)
             .shadow(egui::epaint::Shadow {
                 offset: [0, 8],
@@ -31,24 +86,96 @@ impl<'a> FeaturedSection<'a> {
             .inner_margin(egui::Margin::same(24))
             .show(ui, |ui| {
                 if layout.is_compact() {
-                    Self::hero_copy(ui, featured, layout);
+                    clicked_tag = Self::hero_copy(ui, current, tags, layout);
                     ui.add_space(18.0);
-                    Self::hero_image(ui, layout);
+                    Self::hero_image(ui, current, textures, layout);
                 } else {
                     ui.horizontal(|ui| {
-                        ui.vertical(|ui| {
-                            Self::hero_copy(ui, featured, layout);
-                        });
+                        clicked_tag = ui
+                            .vertical(|ui| Self::hero_copy(ui, current, tags, layout))
+                            .inner;
                         ui.add_space(24.0);
                         ui.vertical(|ui| {
-                            Self::hero_image(ui, layout);
+                            Self::hero_image(ui, current, textures, layout);
                         });
                     });
                 }
-            });
+                if count > 1 {
+                    ui.add_space(16.0);
+                    if let Some(index) = Self::rotation_controls(ui, featured, rotation.index) {
+                        rotation.index = index;
+                        rotation.shown_since = Some(now);
+                    }
+                }
+            })
+            .response;
+
+        if count > 1 {
+            // Hovering the hero pauses the rotation so it does not change while being read.
+            if response.contains_pointer() {
+                rotation.shown_since = Some(now);
+            }
+            let elapsed = now - rotation.shown_since.unwrap_or(now);
+            ui.ctx().request_repaint_after(Duration::from_secs_f64(
+                (ROTATE_INTERVAL - elapsed).max(0.0),
+            ));
+        }
+        ui.ctx().data_mut(|data| data.insert_temp(id, rotation));
+        clicked_tag
     }
 
-    fn hero_copy(ui: &mut egui::Ui, featured: &FeaturedRepo, layout: ResponsiveLayout) {
+    /// Previous/next buttons around one dot per repository. Returns the index to show next.
+    fn rotation_controls(
+        ui: &mut egui::Ui,
+        featured: &[FeaturedRepo],
+        current: usize,
+    ) -> Option<usize> {
+        let count = featured.len();
+        let step_button = |label: &str| {
+            egui::Button::new(egui::RichText::new(label).color(text::PRIMARY))
+                .fill(background::TAG_CHIP)
+                .min_size(egui::vec2(32.0, 28.0))
+                .corner_radius(8.0)
+        };
+        let mut selected = None;
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 8.0;
+            if ui.add(step_button("‹")).on_hover_text("前へ").clicked() {
+                selected = Some((current + count - 1) % count);
+            }
+            for (index, repo) in featured.iter().enumerate() {
+                let active = index == current;
+                let (rect, dot) =
+                    ui.allocate_exact_size(egui::vec2(10.0, 28.0), egui::Sense::click());
+                let color = if active {
+                    text::PRIMARY
+                } else {
+                    text::WHITE_ALPHA_180
+                };
+                let radius = if active || dot.hovered() { 4.5 } else { 3.5 };
+                ui.painter().circle_filled(rect.center(), radius, color);
+                if dot
+                    .on_hover_cursor(egui::CursorIcon::PointingHand)
+                    .on_hover_text(&repo.name)
+                    .clicked()
+                {
+                    selected = Some(index);
+                }
+            }
+            if ui.add(step_button("›")).on_hover_text("次へ").clicked() {
+                selected = Some((current + 1) % count);
+            }
+        });
+        selected
+    }
+
+    /// Title, description, tags and link of `featured`. Returns the tag clicked, if any.
+    fn hero_copy(
+        ui: &mut egui::Ui,
+        featured: &FeaturedRepo,
+        tags: &TagRegistry,
+        layout: ResponsiveLayout,
+    ) -> Option<String> {
         let title_size = if layout.is_compact() { 28.0 } else { 32.0 };
         ui.label(
             egui::RichText::new(&featured.name)
@@ -59,10 +186,14 @@ impl<'a> FeaturedSection<'a> {
         ui.add_space(8.0);
         ui.label(egui::RichText::new(&featured.description).color(text::MUTED));
         ui.add_space(12.0);
+        let mut clicked_tag = None;
         ui.horizontal_wrapped(|ui| {
             ui.spacing_mut().item_spacing.x = 8.0;
-            for tag in &featured.tags {
-                TagChip::new(tag).show(ui);
+            for tag in featured.tags.iter().map(|label| tags.resolve(label)) {
+                let chip = TagChip::new(tag.label()).style(tags.style(tag.key()));
+                if chip.show(ui).clicked() {
+                    clicked_tag = Some(tag.label().to_owned());
+                }
             }
         });
         ui.add_space(18.0);
@@ -79,48 +210,50 @@ impl<'a> FeaturedSection<'a> {
                 ui.ctx().open_url(egui::OpenUrl::new_tab(&repo_url));
             }
         });
+        clicked_tag
     }
 
-    fn hero_image(ui: &mut egui::Ui, layout: ResponsiveLayout) {
+    fn hero_image(
+        ui: &mut egui::Ui,
+        featured: &FeaturedRepo,
+        textures: &mut TextureCache,
+        layout: ResponsiveLayout,
+    ) {
         let max_width = if layout.is_compact() {
             ui.available_width()
         } else {
             320.0
         };
         let max_height = if layout.is_phone() { 170.0 } else { 220.0 };
-        // ui.centered_and_justified(|ui| {
+        let max_size = egui::vec2(max_width, max_height);
         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-            let color_image = Self::logo_image();
-            let texture = ui.ctx().load_texture(
-                "featured-logo",
-                color_image.clone(),
-                egui::TextureOptions::LINEAR,
-            );
-            let [width, height] = color_image.size;
-            let sized_texture = egui::load::SizedTexture::new(
-                texture.id(),
-                egui::vec2(width as f32, height as f32),
-            );
-            let image_widget = egui::Image::new(sized_texture)
-                .maintain_aspect_ratio(true)
-                .max_size(egui::vec2(max_width, max_height))
-                .shrink_to_fit()
-                .corner_radius(12.0);
-            ui.add(image_widget);
+            let remote = featured
+                .image_url
+                .as_deref()
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(|url| textures.texture(ui.ctx(), url));
+            let texture = match remote {
+                Some(TextureState::Failed) | None => {
+                    textures.bundled_texture(ui.ctx(), LOGO_URI, LOGO_BYTES)
+                }
+                Some(state) => state,
+            };
+            match texture {
+                TextureState::Ready(texture) => {
+                    ui.add(
+                        egui::Image::from_texture(texture)
+                            .maintain_aspect_ratio(true)
+                            .max_size(max_size)
+                            .shrink_to_fit()
+                            .corner_radius(12.0),
+                    );
+                }
+                TextureState::Pending => {
+                    ui.add_sized(max_size, egui::Spinner::new());
+                }
+                TextureState::Failed => {}
+            }
         });
     }
-
-    fn logo_image() -> &'static egui::ColorImage {
-        static LOGO_IMAGE: OnceLock<egui::ColorImage> = OnceLock::new();
-        LOGO_IMAGE.get_or_init(|| {
-            let rgba = load_from_memory(include_bytes!("../../../assets/logo.webp"))
-                .expect("embedded logo file should decode")
-                .to_rgba8();
-            let (width, height) = rgba.dimensions();
-            egui::ColorImage::from_rgba_unmultiplied(
-                [width as usize, height as usize],
-                rgba.as_raw(),
-            )
-        })
-    }
 }
@@ -1,14 +1,25 @@
 //! Reusable building blocks that compose the UI layer.
+mod active_filters;
 mod carousel;
+mod diagnostics;
+mod display_settings;
 mod featured;
 mod footer;
 mod navigation;
 mod repo_card;
+mod search_field;
 mod settings_menu;
+mod skipped_notice;
 mod tag_chip;
+mod tag_facets;
 
+pub(crate) use active_filters::ActiveFilters;
 pub(crate) use carousel::RepoCarousel;
+pub(crate) use diagnostics::ParseDiagnostics;
+pub(crate) use display_settings::DisplaySettingsWindow;
 pub(crate) use featured::FeaturedSection;
 pub(crate) use footer::AttributionFooter;
 pub(crate) use navigation::NavigationBar;
 pub(crate) use settings_menu::SettingsAction;
+pub(crate) use skipped_notice::SkippedItemsNotice;
+pub(crate) use tag_facets::TagFacetPanel;
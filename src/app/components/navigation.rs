@@ -1,4 +1,6 @@
+use super::search_field::SearchField;
 use super::settings_menu::{SettingsAction, SettingsMenu};
+use crate::app::data::{QueryError, SearchHistory, SearchSuggestions};
 use crate::app::layout::ResponsiveLayout;
 use crate::app::theme::{background, text};
 
@@ -7,7 +9,7 @@ const SECONDARY_NAV_ITEMS: &[&str] = &["マイリスト"];
 
 /// Renders the shared top navigation bar with search and profile shortcuts.
 pub(crate) struct NavigationBar<'a> {
-    search_query: &'a mut String,
+    search: SearchField<'a>,
     settings_menu: SettingsMenu<'a>,
     layout: ResponsiveLayout,
 }
@@ -15,11 +17,20 @@ pub(crate) struct NavigationBar<'a> {
 impl<'a> NavigationBar<'a> {
     pub(crate) fn new(
         search_query: &'a mut String,
+        search_history: &'a mut SearchHistory,
+        suggestions: &'a SearchSuggestions,
+        query_error: Option<&'a QueryError>,
         settings_menu_open: &'a mut bool,
         layout: ResponsiveLayout,
     ) -> Self {
         Self {
-            search_query,
+            search: SearchField::new(
+                search_query,
+                search_history,
+                suggestions,
+                query_error,
+                layout,
+            ),
             settings_menu: SettingsMenu::new(settings_menu_open),
             layout,
         }
@@ -27,7 +38,7 @@ impl<'a> NavigationBar<'a> {
 
     pub(crate) fn show(self, ui: &mut egui::Ui) -> Option<SettingsAction> {
         let NavigationBar {
-            search_query,
+            search,
             mut settings_menu,
             layout,
         } = self;
@@ -38,9 +49,9 @@ impl<'a> NavigationBar<'a> {
             .corner_radius(12.0)
             .show(ui, |ui| {
                 if layout.is_compact() {
-                    Self::compact(ui, search_query, &mut settings_menu, layout)
+                    Self::compact(ui, search, &mut settings_menu)
                 } else {
-                    Self::spacious(ui, search_query, &mut settings_menu, layout)
+                    Self::spacious(ui, search, &mut settings_menu)
                 }
             })
             .inner
@@ -48,9 +59,8 @@ impl<'a> NavigationBar<'a> {
 
     fn compact(
         ui: &mut egui::Ui,
-        search_query: &mut String,
+        search: SearchField<'_>,
         settings_menu: &mut SettingsMenu<'_>,
-        layout: ResponsiveLayout,
     ) -> Option<SettingsAction> {
         ui.vertical(|ui| {
             let mut action = None;
@@ -63,7 +73,7 @@ impl<'a> NavigationBar<'a> {
                     ui.label(egui::RichText::new("🔔").color(text::PRIMARY));
                 });
             });
-            search_field(ui, search_query, layout);
+            search.show(ui);
             ui.horizontal_wrapped(|ui| {
                 ui.spacing_mut().item_spacing.x = 14.0;
                 for item in PRIMARY_NAV_ITEMS {
@@ -80,9 +90,8 @@ impl<'a> NavigationBar<'a> {
 
     fn spacious(
         ui: &mut egui::Ui,
-        search_query: &mut String,
+        search: SearchField<'_>,
         settings_menu: &mut SettingsMenu<'_>,
-        layout: ResponsiveLayout,
     ) -> Option<SettingsAction> {
         ui.horizontal(|ui| {
             let mut action = None;
@@ -99,7 +108,7 @@ impl<'a> NavigationBar<'a> {
                 ui.add_space(10.0);
                 ui.label(egui::RichText::new("🔔").color(text::PRIMARY));
                 ui.add_space(16.0);
-                search_field(ui, search_query, layout);
+                search.show(ui);
                 ui.add_space(14.0);
                 for item in SECONDARY_NAV_ITEMS.iter().rev() {
                     ui.label(egui::RichText::new(*item).color(text::SECONDARY));
@@ -118,20 +127,3 @@ fn title_label(ui: &mut egui::Ui) {
             .color(text::ACCENT),
     );
 }
-
-fn search_field(ui: &mut egui::Ui, search_query: &mut String, layout: ResponsiveLayout) {
-    let width = layout.search_width();
-    egui::Frame::new()
-        .fill(background::SEARCH_FIELD)
-        .corner_radius(6.0)
-        .inner_margin(egui::Margin::symmetric(8, 4))
-        .show(ui, |ui| {
-            ui.add_sized(
-                [width - 16.0, if layout.is_compact() { 24.0 } else { 22.0 }],
-                egui::TextEdit::singleline(search_query)
-                    .frame(false)
-                    .hint_text("リポジトリ名、技術スタック、キーワードを検索")
-                    .text_color(text::DARK),
-            );
-        });
-}
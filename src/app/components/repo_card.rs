@@ -1,28 +1,62 @@
-use crate::app::data::RepoSummary;
+use std::ops::Range;
+
+use crate::app::data::{RepoSummary, SearchHit, TagRegistry};
 use crate::app::layout::ResponsiveLayout;
+use crate::app::textures::{TextureCache, TextureState};
 use crate::app::theme::{background, stroke, text};
 
 use super::tag_chip::TagChip;
 
-/// Shows a single repository summary as a compact card.
+/// Shows a single repository summary as a compact card, highlighting the
+/// parts of the name and description that matched the search query.
+///
+/// Clicking the card opens the repository; clicking one of its tags does not,
+/// and is reported to the caller instead.
 pub(crate) struct RepoCard<'a> {
     repo: &'a RepoSummary,
+    hit: &'a SearchHit,
+    tags: &'a TagRegistry,
+    textures: &'a mut TextureCache,
     layout: ResponsiveLayout,
 }
 
 impl<'a> RepoCard<'a> {
-    pub(crate) fn new(repo: &'a RepoSummary, layout: ResponsiveLayout) -> Self {
-        Self { repo, layout }
+    pub(crate) fn new(
+        repo: &'a RepoSummary,
+        hit: &'a SearchHit,
+        tags: &'a TagRegistry,
+        textures: &'a mut TextureCache,
+        layout: ResponsiveLayout,
+    ) -> Self {
+        Self {
+            repo,
+            hit,
+            tags,
+            textures,
+            layout,
+        }
     }
 
-    pub(crate) fn show(self, ui: &mut egui::Ui) {
-        let RepoCard { repo, layout } = self;
+    /// Returns the tag clicked on this card, if any.
+    pub(crate) fn show(self, ui: &mut egui::Ui) -> Option<String> {
+        let RepoCard {
+            repo,
+            hit,
+            tags,
+            textures,
+            layout,
+        } = self;
         let card_size = egui::vec2(layout.card_width(), 300.0);
+        let mut clicked_tag = None;
+        // カード全体のクリックは中身より先に登録し、タグのクリックを優先させる
+        let card = egui::UiBuilder::new()
+            .layout(egui::Layout::top_down(egui::Align::Min))
+            .sense(egui::Sense::click());
         let response = ui
-            .allocate_ui_with_layout(card_size, egui::Layout::top_down(egui::Align::Min), |ui| {
+            .scope_builder(card, |ui| {
                 egui::Frame::default()
                     .fill(background::CARD)
-                    .stroke(egui::Stroke::new(1.0, stroke::CARD))
+                    .stroke(egui::Stroke::new(1.0_f32, stroke::CARD))
                     .corner_radius(14.0)
                     .inner_margin(egui::Margin::symmetric(16, 12))
                     .show(ui, |ui| {
@@ -35,43 +69,56 @@ impl<'a> RepoCard<'a> {
                                 .show(ui, |ui| {
                                     ui.set_height(layout.preview_height());
                                     ui.centered_and_justified(|ui| {
-                                        let image_url = repo
+                                        let texture = repo
                                             .image_url
-                                            .as_ref()
-                                            .filter(|url| !url.trim().is_empty());
-                                        if let Some(image_url) = image_url {
-                                            let max_size = egui::vec2(
-                                                ui.available_width(),
-                                                layout.preview_height(),
-                                            );
-                                            let image = egui::Image::from_uri(image_url.clone())
-                                                .maintain_aspect_ratio(true)
-                                                .max_size(max_size)
-                                                .shrink_to_fit()
-                                                .corner_radius(10.0);
-                                            ui.add(image);
-                                        } else {
-                                            let fallback_icon = egui::RichText::new("🖼")
-                                                .color(text::WHITE_ALPHA_180)
-                                                .size(layout.preview_height() * 0.5);
-                                            ui.add(
-                                                egui::Label::new(fallback_icon).selectable(false),
-                                            );
+                                            .as_deref()
+                                            .map(str::trim)
+                                            .filter(|url| !url.is_empty())
+                                            .map(|url| textures.texture(ui.ctx(), url));
+                                        match texture {
+                                            Some(TextureState::Ready(texture)) => {
+                                                let max_size = egui::vec2(
+                                                    ui.available_width(),
+                                                    layout.preview_height(),
+                                                );
+                                                let image = egui::Image::from_texture(texture)
+                                                    .maintain_aspect_ratio(true)
+                                                    .max_size(max_size)
+                                                    .shrink_to_fit()
+                                                    .corner_radius(10.0);
+                                                ui.add(image);
+                                            }
+                                            Some(TextureState::Pending) => {
+                                                ui.spinner();
+                                            }
+                                            Some(TextureState::Failed) | None => {
+                                                let fallback_icon = egui::RichText::new("🖼")
+                                                    .color(text::WHITE_ALPHA_180)
+                                                    .size(layout.preview_height() * 0.5);
+                                                ui.add(
+                                                    egui::Label::new(fallback_icon)
+                                                        .selectable(false),
+                                                );
+                                            }
                                         }
                                     });
                                 });
 
                             ui.add_space(10.0);
-                            ui.label(
-                                egui::RichText::new(&repo.name)
-                                    .strong()
-                                    .color(text::PRIMARY),
-                            );
-                            ui.label(
-                                egui::RichText::new(&repo.description)
-                                    .small()
-                                    .color(text::SECONDARY),
-                            );
+                            ui.label(highlighted(
+                                ui,
+                                &repo.name,
+                                &hit.name,
+                                egui::TextStyle::Body,
+                                text::PRIMARY,
+                            ));
+                            ui.label(highlighted(
+                                ui,
+                                &repo.description,
+                                &hit.description,
+                                egui::TextStyle::Small,
+                                text::SECONDARY,
+                            ));
                             ui.add_space(4.0);
                             if let Some(updated_at) = repo
                                 .updated_at
@@ -85,16 +132,16 @@ impl<'a> RepoCard<'a> {
                                         .color(text::PRIMARY),
                                 );
                             }
-                            if let Some(badges) = &repo.badges {
+                            if !repo.badges.is_empty() {
                                 ui.add_space(4.0);
                                 ui.horizontal_wrapped(|ui| {
                                     ui.spacing_mut().item_spacing.x = 4.0;
-                                    for badge in badges
-                                        .split(',')
-                                        .map(|s| s.trim())
-                                        .filter(|s| !s.is_empty())
-                                    {
-                                        TagChip::new(badge).show(ui);
+                                    for badge in &repo.badges {
+                                        let chip = TagChip::new(badge.label())
+                                            .style(tags.style(badge.key()));
+                                        if chip.show(ui).clicked() {
+                                            clicked_tag = Some(badge.label().to_owned());
+                                        }
                                     }
                                 });
                             }
@@ -102,13 +149,39 @@ impl<'a> RepoCard<'a> {
                     });
             })
             .response
-            .interact(egui::Sense::click());
-        if response.clicked() {
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+        if response.clicked() && clicked_tag.is_none() {
             let repo_url = repo.repo_url.trim();
             if !repo_url.is_empty() {
                 ui.ctx()
                     .open_url(egui::OpenUrl::new_tab(repo_url.to_owned()));
             }
         }
+        clicked_tag
+    }
+}
+
+/// `text` in `style` and `color`, with the byte `ranges` that matched the search on a highlight.
+fn highlighted(
+    ui: &egui::Ui,
+    text: &str,
+    ranges: &[Range<usize>],
+    style: egui::TextStyle,
+    color: egui::Color32,
+) -> egui::text::LayoutJob {
+    let font_id = style.resolve(ui.style());
+    let plain = egui::TextFormat::simple(font_id.clone(), color);
+    let matched = egui::TextFormat {
+        background: background::SEARCH_MATCH,
+        ..egui::TextFormat::simple(font_id, text::PRIMARY)
+    };
+    let mut job = egui::text::LayoutJob::default();
+    let mut end = 0;
+    for range in ranges {
+        job.append(&text[end..range.start], 0.0, plain.clone());
+        job.append(&text[range.clone()], 0.0, matched.clone());
+        end = range.end;
     }
+    job.append(&text[end..], 0.0, plain);
+    job
 }
@@ -0,0 +1,200 @@
+use crate::app::data::{QueryError, SearchHistory, SearchSuggestions, Suggestion, SuggestionKind};
+use crate::app::layout::ResponsiveLayout;
+use crate::app::theme::{background, text};
+
+/// Search box of the navigation bar with a dropdown of recent queries and
+/// completions.
+///
+/// The dropdown opens while the field has focus. Arrow keys move the
+/// selection, Enter applies it, and Escape closes the dropdown together with
+/// the field. Pressing Enter on the query itself records it in the history.
+pub(crate) struct SearchField<'a> {
+    query: &'a mut String,
+    history: &'a mut SearchHistory,
+    suggestions: &'a SearchSuggestions,
+    error: Option<&'a QueryError>,
+    layout: ResponsiveLayout,
+}
+
+/// Dropdown state kept in egui's temporary memory between frames.
+#[derive(Clone, Default)]
+struct DropdownState {
+    selected: Option<usize>,
+    /// Where the dropdown was drawn last frame, so a click on it is not
+    /// mistaken for a click that closes it.
+    rect: Option<egui::Rect>,
+}
+
+impl<'a> SearchField<'a> {
+    pub(crate) fn new(
+        query: &'a mut String,
+        history: &'a mut SearchHistory,
+        suggestions: &'a SearchSuggestions,
+        error: Option<&'a QueryError>,
+        layout: ResponsiveLayout,
+    ) -> Self {
+        Self {
+            query,
+            history,
+            suggestions,
+            error,
+            layout,
+        }
+    }
+
+    pub(crate) fn show(self, ui: &mut egui::Ui) {
+        let width = self.layout.search_width();
+        // 右から左のレイアウトでもエラーが欄の下に来るよう、縦にまとめる
+        ui.allocate_ui_with_layout(
+            egui::vec2(width, 0.0),
+            egui::Layout::top_down(egui::Align::Min),
+            |ui| {
+                ui.spacing_mut().item_spacing.y = 4.0;
+                self.field(ui, width);
+            },
+        );
+    }
+
+    fn field(self, ui: &mut egui::Ui, width: f32) {
+        let SearchField {
+            query,
+            history,
+            suggestions,
+            error,
+            layout,
+        } = self;
+        let id = egui::Id::new("search-field");
+        let ctx = ui.ctx().clone();
+        let mut state: DropdownState = ctx.data(|data| data.get_temp(id).unwrap_or_default());
+
+        let pointer_on_dropdown = state
+            .rect
+            .zip(ctx.pointer_hover_pos())
+            .is_some_and(|(rect, pointer)| rect.contains(pointer));
+        let open = ctx.memory(|memory| memory.has_focus(id)) || pointer_on_dropdown;
+        let entries = if open {
+            suggestions.suggest(query, history)
+        } else {
+            Vec::new()
+        };
+        if entries.is_empty() {
+            state.selected = None;
+        }
+
+        // 入力欄より先にキーを取り、カーソル移動や確定として扱われないようにする
+        let mut chosen = None;
+        if !entries.is_empty() && ctx.memory(|memory| memory.has_focus(id)) {
+            ui.input_mut(|input| {
+                let last = entries.len() - 1;
+                if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                    state.selected = Some(state.selected.map_or(0, |index| (index + 1).min(last)));
+                }
+                if input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                    state.selected = state.selected.and_then(|index| index.checked_sub(1));
+                }
+                if state.selected.is_some()
+                    && input.consume_key(egui::Modifiers::NONE, egui::Key::Enter)
+                {
+                    chosen = state.selected;
+                }
+            });
+        }
+
+        let response = egui::Frame::new()
+            .fill(background::SEARCH_FIELD)
+            .corner_radius(6.0)
+            .inner_margin(egui::Margin::symmetric(8, 4))
+            .show(ui, |ui| {
+                ui.add_sized(
+                    [width - 16.0, if layout.is_compact() { 24.0 } else { 22.0 }],
+                    egui::TextEdit::singleline(query)
+                        .id(id)
+                        .frame(false)
+                        .hint_text("リポジトリ名、技術スタック、キーワードを検索（tag:rust など）")
+                        .text_color(text::DARK),
+                )
+                .on_hover_text(
+                    "tag:rust, name:foo, section:\"Tools\", updated:>2024-01-01, \
+                     -python（除外）, \"完全一致\" が使えます",
+                )
+            });
+        let field = response.inner;
+        if field.changed() {
+            state.selected = None;
+        }
+        if field.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+            history.record(query);
+        }
+
+        state.rect = None;
+        if !entries.is_empty() {
+            let area = egui::Area::new(id.with("dropdown"))
+                .order(egui::Order::Foreground)
+                .fixed_pos(response.response.rect.left_bottom() + egui::vec2(0.0, 4.0))
+                .show(&ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(width - 12.0);
+                        for (index, entry) in entries.iter().enumerate() {
+                            let label = format!("{}  {}", kind_icon(entry.kind), entry.label);
+                            let selected = state.selected == Some(index);
+                            if ui.add(egui::Button::selectable(selected, label)).clicked() {
+                                chosen = Some(index);
+                            }
+                        }
+                    });
+                });
+            state.rect = Some(area.response.rect);
+        }
+
+        if let Some(entry) = chosen.and_then(|index| entries.get(index)) {
+            apply(&ctx, id, query, history, entry);
+            state.selected = None;
+        }
+        ctx.data_mut(|data| data.insert_temp(id, state));
+
+        if let Some(error) = error {
+            let column = query
+                .get(..error.span.start)
+                .map_or(0, |before| before.chars().count())
+                + 1;
+            ui.add(
+                egui::Label::new(
+                    egui::RichText::new(format!("⚠ {column} 文字目: {error}"))
+                        .small()
+                        .color(text::WARNING),
+                )
+                .wrap(),
+            );
+        }
+    }
+}
+
+/// Replaces the query with `entry` and keeps typing at its end.
+fn apply(
+    ctx: &egui::Context,
+    id: egui::Id,
+    query: &mut String,
+    history: &mut SearchHistory,
+    entry: &Suggestion,
+) {
+    entry.query.clone_into(query);
+    if entry.kind == SuggestionKind::History {
+        history.record(query);
+    }
+    let mut text_state = egui::TextEdit::load_state(ctx, id).unwrap_or_default();
+    let end = egui::text::CCursor::new(query.chars().count());
+    text_state
+        .cursor
+        .set_char_range(Some(egui::text::CCursorRange::one(end)));
+    text_state.store(ctx, id);
+    ctx.memory_mut(|memory| memory.request_focus(id));
+}
+
+fn kind_icon(kind: SuggestionKind) -> &'static str {
+    match kind {
+        SuggestionKind::History => "🕘",
+        SuggestionKind::Tag => "🏷",
+        SuggestionKind::Section => "📁",
+        SuggestionKind::Repo => "📦",
+    }
+}
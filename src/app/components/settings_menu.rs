@@ -3,6 +3,7 @@ use crate::app::theme::{background, button, stroke, text};
 /// Actions emitted from the settings menu.
 pub(crate) enum SettingsAction {
     RefreshRepoData,
+    OpenDisplaySettings,
     ClearCache,
 }
 
@@ -59,7 +60,7 @@ impl<'a> SettingsMenu<'a> {
         let popup = popup_area.show(ctx, |ui| {
             egui::Frame::popup(ui.style())
                 .fill(background::CARD)
-                .stroke(egui::Stroke::new(1.0, stroke::CARD))
+                .stroke(egui::Stroke::new(1.0_f32, stroke::CARD))
                 .corner_radius(10.0)
                 .inner_margin(egui::Margin::symmetric(12, 8))
                 .show(ui, |ui| {
@@ -74,8 +75,9 @@ impl<'a> SettingsMenu<'a> {
 
                     ui.add_space(4.0);
 
-                    if menu_item(ui, "表示設定 (準備中)").clicked() {
+                    if menu_item(ui, "表示設定").clicked() {
                         close = true;
+                        action = Some(SettingsAction::OpenDisplaySettings);
                     }
 
                     if menu_item(ui, "キャッシュを削除").clicked() {
@@ -0,0 +1,75 @@
+use super::diagnostics::excerpt_line;
+use crate::app::data::ParseWarning;
+use crate::app::theme::{background, text};
+
+/// Collapsible "N items skipped" notice listing the entries that failed to parse,
+/// with the lines each one failed on when they could be found.
+pub(crate) struct SkippedItemsNotice<'a> {
+    warnings: &'a [ParseWarning],
+}
+
+impl<'a> SkippedItemsNotice<'a> {
+    pub(crate) fn new(warnings: &'a [ParseWarning]) -> Self {
+        Self { warnings }
+    }
+
+    /// Draws nothing when every entry was parsed.
+    pub(crate) fn show(self, ui: &mut egui::Ui) {
+        if self.warnings.is_empty() {
+            return;
+        }
+
+        let title = egui::RichText::new(format!(
+            "⚠ {}件の項目を読み込めなかったためスキップしました",
+            self.warnings.len()
+        ))
+        .color(text::WARNING);
+        egui::CollapsingHeader::new(title)
+            .id_salt("skipped-items-notice")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing.y = 6.0;
+                for warning in self.warnings {
+                    warning_row(ui, warning);
+                }
+            });
+    }
+}
+
+fn warning_row(ui: &mut egui::Ui, warning: &ParseWarning) {
+    ui.vertical(|ui| {
+        ui.spacing_mut().item_spacing.y = 2.0;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                egui::RichText::new(warning.field_path())
+                    .monospace()
+                    .color(text::PRIMARY),
+            );
+            if let Some(name) = &warning.name {
+                ui.label(egui::RichText::new(name).strong().color(text::PRIMARY));
+            }
+        });
+        ui.label(egui::RichText::new(&warning.message).color(text::SECONDARY));
+        let source = match warning.location {
+            Some(location) => format!(
+                "{}（{}行 {}列）",
+                warning.source, location.line, location.column
+            ),
+            None => warning.source.to_string(),
+        };
+        ui.label(egui::RichText::new(source).small().color(text::SECONDARY));
+
+        if !warning.excerpt.is_empty() {
+            egui::Frame::default()
+                .fill(background::CODE)
+                .corner_radius(6.0)
+                .inner_margin(egui::Margin::symmetric(10, 8))
+                .show(ui, |ui| {
+                    ui.spacing_mut().item_spacing.y = 0.0;
+                    for line in &warning.excerpt {
+                        ui.label(excerpt_line(line, warning.location));
+                    }
+                });
+        }
+    });
+}
@@ -1,28 +1,73 @@
 use std::borrow::Cow;
 
+use crate::app::data::TagStyle;
 use crate::app::theme::{background, text};
 
 /// Compact chip-like label for tags or metadata such as star counts.
+///
+/// Chips sense clicks so a tag can be used as a filter; a removable chip
+/// stands for an active filter and shows a cross. Tags registered in the app
+/// config are tinted with their colour and prefixed with their icon.
 pub(crate) struct TagChip<'a> {
     text: Cow<'a, str>,
+    style: Option<&'a TagStyle>,
+    removable: bool,
 }
 
 impl<'a> TagChip<'a> {
     pub(crate) fn new(text: impl Into<Cow<'a, str>>) -> Self {
-        Self { text: text.into() }
+        Self {
+            text: text.into(),
+            style: None,
+            removable: false,
+        }
     }
 
-    pub(crate) fn show(self, ui: &mut egui::Ui) {
-        egui::Frame::default()
-            .fill(background::TAG_CHIP)
-            .stroke(egui::Stroke::new(1.0, text::ACCENT))
+    /// Applies the colour and icon of a registered tag, if any.
+    pub(crate) fn style(mut self, style: Option<&'a TagStyle>) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Styles the chip as an active filter that is removed by clicking it.
+    pub(crate) fn removable(mut self) -> Self {
+        self.removable = true;
+        self
+    }
+
+    pub(crate) fn show(self, ui: &mut egui::Ui) -> egui::Response {
+        let color = self.style.and_then(|style| style.color);
+        let mut label = match self.style.and_then(|style| style.icon.as_deref()) {
+            Some(icon) => format!("{icon} {}", self.text),
+            None => self.text.into_owned(),
+        };
+        let fill = if self.removable {
+            label.push_str("  ✕");
+            background::TAG_CHIP_ACTIVE
+        } else {
+            color.map_or(background::TAG_CHIP, |color| color.gamma_multiply(0.35))
+        };
+        let response = egui::Frame::default()
+            .fill(fill)
+            .stroke(egui::Stroke::new(1.0_f32, color.unwrap_or(text::ACCENT)))
             .corner_radius(10.0)
             .show(ui, |ui| {
-                ui.label(
-                    egui::RichText::new(self.text.as_ref())
-                        .color(text::WHITE_ALPHA_180)
-                        .small(),
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(label)
+                            .color(text::WHITE_ALPHA_180)
+                            .small(),
+                    )
+                    .selectable(false),
                 );
-            });
+            })
+            .response
+            .interact(egui::Sense::click())
+            .on_hover_cursor(egui::CursorIcon::PointingHand);
+        if self.removable {
+            response.on_hover_text("絞り込みを解除")
+        } else {
+            response.on_hover_text("このタグで絞り込む")
+        }
     }
 }
@@ -0,0 +1,61 @@
+use crate::app::data::{TagCount, TagMatch, TagSelection};
+use crate::app::theme::text;
+
+/// Badge checklist with counts, used as the sidebar and as the compact drawer.
+pub(crate) struct TagFacetPanel<'a> {
+    facets: &'a [TagCount],
+    selection: &'a mut TagSelection,
+}
+
+impl<'a> TagFacetPanel<'a> {
+    pub(crate) fn new(facets: &'a [TagCount], selection: &'a mut TagSelection) -> Self {
+        Self { facets, selection }
+    }
+
+    pub(crate) fn show(self, ui: &mut egui::Ui) {
+        let TagFacetPanel { facets, selection } = self;
+
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("タグで絞り込み")
+                    .strong()
+                    .color(text::PRIMARY),
+            );
+            if !selection.is_empty() {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("クリア").clicked() {
+                        selection.clear();
+                    }
+                });
+            }
+        });
+        ui.horizontal(|ui| {
+            for mode in TagMatch::ALL {
+                ui.selectable_value(&mut selection.mode, mode, mode.label());
+            }
+        });
+        ui.separator();
+
+        if facets.is_empty() {
+            ui.label(egui::RichText::new("タグがありません").color(text::MUTED));
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("tag-facets")
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for facet in facets {
+                    let mut checked = selection.is_selected(&facet.key);
+                    let label = egui::RichText::new(format!("{} ({})", facet.label, facet.count))
+                        .color(if facet.count == 0 {
+                            text::MUTED
+                        } else {
+                            text::SECONDARY
+                        });
+                    if ui.checkbox(&mut checked, label).changed() {
+                        selection.toggle(&facet.key);
+                    }
+                }
+            });
+    }
+}
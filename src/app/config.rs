@@ -0,0 +1,251 @@
+//! Application configuration that decides where portfolio data is loaded from.
+//!
+//! The embedded `assets/config.yaml` provides the defaults. Native builds can
+//! point at another config file or list sources on the command line, and web
+//! builds accept `?source=...` query parameters.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use super::data::TagRegistry;
+
+const DEFAULT_CONFIG_YAML: &str = include_str!("../../assets/config.yaml");
+
+/// A single location that provides a portfolio document.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(from = "String", into = "String")]
+pub(crate) enum PortfolioSource {
+    /// Fetched over HTTP(S). On the web, relative URLs resolve against the page.
+    Url(String),
+    /// Read from the local file system (native only).
+    #[cfg(not(target_arch = "wasm32"))]
+    Path(PathBuf),
+}
+
+impl PortfolioSource {
+    /// Interprets `value` as a URL when it has a scheme, otherwise as a local path on native.
+    pub(crate) fn parse(value: &str) -> Self {
+        let value = value.trim();
+        #[cfg(not(target_arch = "wasm32"))]
+        if !value.contains("://") {
+            return PortfolioSource::Path(PathBuf::from(value));
+        }
+        PortfolioSource::Url(value.to_owned())
+    }
+
+    /// The URL of a source fetched over HTTP(S), or `None` for a local file.
+    pub(crate) fn url(&self) -> Option<&str> {
+        match self {
+            PortfolioSource::Url(url) => Some(url),
+            #[cfg(not(target_arch = "wasm32"))]
+            PortfolioSource::Path(_) => None,
+        }
+    }
+}
+
+impl From<String> for PortfolioSource {
+    fn from(value: String) -> Self {
+        PortfolioSource::parse(&value)
+    }
+}
+
+impl From<PortfolioSource> for String {
+    fn from(source: PortfolioSource) -> Self {
+        source.to_string()
+    }
+}
+
+impl std::fmt::Display for PortfolioSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortfolioSource::Url(url) => f.write_str(url),
+            #[cfg(not(target_arch = "wasm32"))]
+            PortfolioSource::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Settings read from the app config file.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) struct AppConfig {
+    /// Portfolio sources whose sections are merged in declared order.
+    #[serde(default)]
+    pub(crate) sources: Vec<PortfolioSource>,
+    /// Canonical tags with their aliases, colours and icons.
+    #[serde(default)]
+    pub(crate) tags: TagRegistry,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig::from_yaml(DEFAULT_CONFIG_YAML).expect("embedded config YAML should be valid")
+    }
+}
+
+impl AppConfig {
+    pub(crate) fn from_yaml(text: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(text)
+    }
+
+    /// Replaces the configured sources when `sources` is non-empty.
+    pub(crate) fn override_sources<'s>(&mut self, sources: impl IntoIterator<Item = &'s str>) {
+        let sources: Vec<_> = sources
+            .into_iter()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(PortfolioSource::parse)
+            .collect();
+        if !sources.is_empty() {
+            self.sources = sources;
+        }
+    }
+
+    /// Resolves the config for the running platform, falling back to the embedded defaults.
+    pub(crate) fn resolve(cc: &eframe::CreationContext<'_>) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = cc;
+            AppConfig::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+                log::error!("Failed to load app config: {err}");
+                AppConfig::default()
+            })
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let mut config = AppConfig::default();
+            if let Some(sources) = cc
+                .integration_info
+                .web_info
+                .location
+                .query_map
+                .get("source")
+            {
+                config.override_sources(sources.iter().map(String::as_str));
+            }
+            config
+        }
+    }
+
+    /// Builds the config from command line arguments.
+    ///
+    /// `--config <path>` loads another config file, and any `--source <value>`
+    /// or bare arguments replace its sources.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config_path = None;
+        let mut sources = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" | "--source" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for {arg}"))?;
+                    if arg == "--config" {
+                        config_path = Some(PathBuf::from(value));
+                    } else {
+                        sources.push(value);
+                    }
+                }
+                _ => sources.push(arg),
+            }
+        }
+
+        let mut config = match config_path {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+                AppConfig::from_yaml(&text).map_err(|e| format!("{}: {e}", path.display()))?
+            }
+            None => AppConfig::default(),
+        };
+        config.override_sources(sources.iter().map(String::as_str));
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_config_has_sources() {
+        let config = AppConfig::default();
+        assert!(
+            !config.sources.is_empty(),
+            "embedded config should list sources"
+        );
+        assert!(matches!(config.sources[0], PortfolioSource::Url(_)));
+    }
+
+    #[test]
+    fn parse_distinguishes_urls_from_paths() {
+        assert_eq!(
+            PortfolioSource::parse("https://example.com/portfolio.yaml"),
+            PortfolioSource::Url("https://example.com/portfolio.yaml".to_string())
+        );
+        #[cfg(not(target_arch = "wasm32"))]
+        assert_eq!(
+            PortfolioSource::parse("./portfolio.yaml"),
+            PortfolioSource::Path(PathBuf::from("./portfolio.yaml"))
+        );
+    }
+
+    #[test]
+    fn config_yaml_keeps_declared_order() {
+        let config = AppConfig::from_yaml(
+            "sources:\n  - https://example.com/a.yaml\n  - https://example.com/b.yaml\n",
+        )
+        .expect("config should parse");
+        let sources: Vec<String> = config.sources.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sources,
+            ["https://example.com/a.yaml", "https://example.com/b.yaml"]
+        );
+    }
+
+    #[test]
+    fn config_yaml_reads_tag_registry() {
+        let config = AppConfig::from_yaml(
+            "sources: []\ntags:\n  - name: WebAssembly\n    aliases: [WASM]\n",
+        )
+        .expect("config should parse");
+        assert_eq!(config.tags.resolve("wasm").label(), "WebAssembly");
+
+        let invalid = AppConfig::from_yaml("tags:\n  - name: Rust\n    color: rust\n");
+        assert!(invalid.is_err(), "colours must be hex");
+    }
+
+    #[test]
+    fn override_sources_ignores_empty_input() {
+        let mut config = AppConfig::default();
+        let original = config.clone();
+        config.override_sources(["", "  "]);
+        assert_eq!(config, original);
+
+        config.override_sources(["https://example.com/other.yaml"]);
+        assert_eq!(
+            config.sources,
+            [PortfolioSource::Url(
+                "https://example.com/other.yaml".to_string()
+            )]
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn from_args_collects_sources() {
+        let args = ["--source", "https://example.com/a.yaml", "local.yaml"].map(String::from);
+        let config = AppConfig::from_args(args).expect("args should parse");
+        assert_eq!(
+            config.sources,
+            [
+                PortfolioSource::Url("https://example.com/a.yaml".to_string()),
+                PortfolioSource::Path(PathBuf::from("local.yaml")),
+            ]
+        );
+
+        let missing = AppConfig::from_args(["--config".to_string()]);
+        assert!(missing.is_err(), "--config without a value should fail");
+    }
+}
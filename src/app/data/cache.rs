@@ -0,0 +1,193 @@
+//! Offline cache of portfolio responses together with their HTTP validators.
+//!
+//! The last good body of every URL source is persisted with the app state so
+//! the portfolio can still be shown when the network is unavailable, and
+//! refreshes can be revalidated with `If-None-Match` / `If-Modified-Since`.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::PortfolioFormat;
+
+/// Last successfully parsed response of a single URL.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) struct CachedResponse {
+    pub(crate) body: String,
+    #[serde(default)]
+    pub(crate) etag: Option<String>,
+    #[serde(default)]
+    pub(crate) last_modified: Option<String>,
+    /// `Content-Type` of the response, used to pick the document format.
+    #[serde(default)]
+    pub(crate) content_type: Option<String>,
+    /// Unix time in seconds when the body was last fetched or revalidated.
+    pub(crate) fetched_at: u64,
+}
+
+impl CachedResponse {
+    pub(crate) fn from_response(response: &ehttp::Response, body: &str, now: u64) -> Self {
+        Self {
+            body: body.to_owned(),
+            etag: response.headers.get("etag").map(str::to_owned),
+            last_modified: response.headers.get("last-modified").map(str::to_owned),
+            content_type: response.content_type().map(str::to_owned),
+            fetched_at: now,
+        }
+    }
+
+    /// Format of the cached body, detected the same way as for a fresh response.
+    pub(crate) fn format(&self, url: &str) -> PortfolioFormat {
+        PortfolioFormat::detect(self.content_type.as_deref(), url, &self.body)
+    }
+
+    /// Adds the validators so the server can answer `304 Not Modified`.
+    ///
+    /// Browsers already revalidate through their own HTTP cache, and the extra
+    /// headers would trigger CORS preflights, so this only applies natively.
+    pub(crate) fn apply_conditional_headers(&self, request: &mut ehttp::Request) {
+        if cfg!(target_arch = "wasm32") {
+            return;
+        }
+        if let Some(etag) = &self.etag {
+            request.headers.insert("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request.headers.insert("If-Modified-Since", last_modified);
+        }
+    }
+}
+
+/// Cached responses keyed by source URL.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub(crate) struct PortfolioCache {
+    entries: BTreeMap<String, CachedResponse>,
+}
+
+impl PortfolioCache {
+    pub(crate) fn get(&self, url: &str) -> Option<&CachedResponse> {
+        self.entries.get(url)
+    }
+
+    pub(crate) fn insert(&mut self, url: &str, entry: CachedResponse) {
+        self.entries.insert(url.to_owned(), entry);
+    }
+
+    /// Marks the cached body of `url` as confirmed fresh at `now`.
+    pub(crate) fn mark_revalidated(&mut self, url: &str, now: u64) {
+        if let Some(entry) = self.entries.get_mut(url) {
+            entry.fetched_at = now;
+        }
+    }
+
+    /// Drops entries for URLs that are no longer configured.
+    pub(crate) fn retain_urls(&mut self, urls: &[&str]) {
+        self.entries.retain(|url, _| urls.contains(&url.as_str()));
+    }
+
+    /// Age of the oldest cached entry, i.e. how stale the shown data may be.
+    pub(crate) fn age(&self, now: u64) -> Option<Duration> {
+        self.entries
+            .values()
+            .map(|entry| entry.fetched_at)
+            .min()
+            .map(|fetched_at| Duration::from_secs(now.saturating_sub(fetched_at)))
+    }
+}
+
+/// Current Unix time in seconds. Works on the web as well as natively.
+pub(crate) fn unix_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Formats a cache age as a short Japanese relative time such as "5分前".
+pub(crate) fn describe_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => "たった今".to_owned(),
+        60..3_600 => format!("{}分前", secs / 60),
+        3_600..86_400 => format!("{}時間前", secs / 3_600),
+        _ => format!("{}日前", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_with_headers(headers: &[(&str, &str)]) -> ehttp::Response {
+        ehttp::Response {
+            url: "https://example.com/portfolio.yaml".to_string(),
+            ok: true,
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: ehttp::Headers::new(headers),
+            bytes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn from_response_keeps_validators() {
+        let response = response_with_headers(&[
+            ("ETag", "\"abc\""),
+            ("Last-Modified", "Wed, 01 Jan 2025 00:00:00 GMT"),
+        ]);
+        let entry = CachedResponse::from_response(&response, "[]", 42);
+        assert_eq!(entry.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(
+            entry.last_modified.as_deref(),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+        assert_eq!(entry.fetched_at, 42);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn conditional_headers_are_sent_natively() {
+        let entry = CachedResponse {
+            body: "[]".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            content_type: None,
+            fetched_at: 0,
+        };
+        let mut request = ehttp::Request::get("https://example.com/portfolio.yaml");
+        entry.apply_conditional_headers(&mut request);
+        assert_eq!(request.headers.get("If-None-Match"), Some("\"abc\""));
+        assert_eq!(request.headers.get("If-Modified-Since"), None);
+    }
+
+    #[test]
+    fn age_reports_oldest_entry() {
+        let entry = |fetched_at| CachedResponse {
+            body: "[]".to_string(),
+            etag: None,
+            last_modified: None,
+            content_type: None,
+            fetched_at,
+        };
+        let mut cache = PortfolioCache::default();
+        assert_eq!(cache.age(1_000), None);
+
+        cache.insert("https://example.com/a.yaml", entry(900));
+        cache.insert("https://example.com/b.yaml", entry(400));
+        assert_eq!(cache.age(1_000), Some(Duration::from_secs(600)));
+
+        cache.mark_revalidated("https://example.com/b.yaml", 1_000);
+        assert_eq!(cache.age(1_000), Some(Duration::from_secs(100)));
+
+        cache.retain_urls(&["https://example.com/b.yaml"]);
+        assert_eq!(cache.age(1_000), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn describe_age_uses_largest_unit() {
+        assert_eq!(describe_age(Duration::from_secs(5)), "たった今");
+        assert_eq!(describe_age(Duration::from_secs(150)), "2分前");
+        assert_eq!(describe_age(Duration::from_secs(7_200)), "2時間前");
+        assert_eq!(describe_age(Duration::from_secs(3 * 86_400)), "3日前");
+    }
+}
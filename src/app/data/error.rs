@@ -0,0 +1,384 @@
+//! Structured errors for portfolio loading.
+
+use std::fmt;
+
+use super::PortfolioFormat;
+use crate::app::config::PortfolioSource;
+
+/// Line and column (both 1-based) where a document failed to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TextLocation {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// A numbered line of the document shown around a parse error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ExcerptLine {
+    pub(crate) number: usize,
+    pub(crate) text: String,
+}
+
+/// Number of lines shown before and after the offending line.
+const EXCERPT_RADIUS: usize = 2;
+
+/// Where and why a document failed to parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ParseDiagnostic {
+    pub(crate) format: PortfolioFormat,
+    /// Error message without the path and location.
+    pub(crate) message: String,
+    pub(crate) location: Option<TextLocation>,
+    /// Path to the offending value, e.g. `[2].items[5].repo_url`.
+    pub(crate) path: Option<String>,
+    /// Lines around `location`, including the offending one.
+    pub(crate) excerpt: Vec<ExcerptLine>,
+}
+
+/// What went wrong while loading a single source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PortfolioErrorKind {
+    /// No response arrived, e.g. DNS failure or connection refused.
+    Network(String),
+    /// The server answered with a non-success status.
+    Http { status: u16, status_text: String },
+    /// The response body is not valid UTF-8.
+    InvalidUtf8,
+    /// The document is not a valid portfolio.
+    Parse(Box<ParseDiagnostic>),
+    /// A local file could not be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    Io(String),
+}
+
+impl PortfolioErrorKind {
+    /// Builds a diagnostic from a serde_yaml error and the document it came from.
+    ///
+    /// Besides syntax errors, typed reads such as the featured repository report
+    /// missing fields and invalid values here. serde_yaml formats located errors as `"{path}: {message} at line L column C"`,
+    /// so the path is recovered from that text.
+    pub(crate) fn from_yaml(err: &serde_yaml::Error, text: &str) -> Self {
+        let location = err.location().map(|location| TextLocation {
+            line: location.line(),
+            column: location.column(),
+        });
+
+        let mut message = err.to_string();
+        if let Some(location) = location {
+            let suffix = format!(" at line {} column {}", location.line, location.column);
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_owned();
+            }
+        }
+
+        let mut path = None;
+        if let Some((prefix, rest)) = message.split_once(": ") {
+            if !prefix.is_empty() && !prefix.contains(char::is_whitespace) {
+                // ルートの配列は ".[1].items" のように表記されるので先頭の "." を除く
+                path = Some(prefix.strip_prefix('.').unwrap_or(prefix).to_owned());
+                message = rest.to_owned();
+            }
+        }
+        // 必須フィールドの欠落は欠けているフィールド名までを指し示す
+        if let Some(field) = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.strip_suffix('`'))
+        {
+            path = Some(match path {
+                Some(parent) => format!("{parent}.{field}"),
+                None => field.to_owned(),
+            });
+        }
+
+        PortfolioErrorKind::parse(PortfolioFormat::Yaml, message, location, path, text)
+    }
+
+    /// Builds a diagnostic from a serde_json error. JSON errors carry no path.
+    pub(crate) fn from_json(err: &serde_json::Error, text: &str) -> Self {
+        let location = (err.line() > 0).then(|| TextLocation {
+            line: err.line(),
+            column: err.column().max(1),
+        });
+        let mut message = err.to_string();
+        if let Some(location) = location {
+            let suffix = format!(" at line {} column {}", location.line, err.column());
+            if let Some(stripped) = message.strip_suffix(&suffix) {
+                message = stripped.to_owned();
+            }
+        }
+        PortfolioErrorKind::parse(PortfolioFormat::Json, message, location, None, text)
+    }
+
+    /// Builds a diagnostic from a toml error, converting its byte span to a location.
+    pub(crate) fn from_toml(err: &toml::de::Error, text: &str) -> Self {
+        let location = err
+            .span()
+            .map(|span| location_of(text, span.start.min(text.len())));
+        PortfolioErrorKind::parse(
+            PortfolioFormat::Toml,
+            err.message().trim_end().to_owned(),
+            location,
+            None,
+            text,
+        )
+    }
+
+    /// Diagnostic for a document that parsed but does not have the expected structure.
+    pub(crate) fn invalid_shape(format: PortfolioFormat, message: &str) -> Self {
+        PortfolioErrorKind::parse(format, message.to_owned(), None, None, "")
+    }
+
+    fn parse(
+        format: PortfolioFormat,
+        message: String,
+        location: Option<TextLocation>,
+        path: Option<String>,
+        text: &str,
+    ) -> Self {
+        PortfolioErrorKind::Parse(Box::new(ParseDiagnostic {
+            format,
+            message,
+            location,
+            path,
+            excerpt: location
+                .map(|location| excerpt(text, location.line))
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Whether retrying the same request may succeed.
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            PortfolioErrorKind::Network(_) => true,
+            PortfolioErrorKind::Http { status, .. } => {
+                matches!(status, 408 | 429) || *status >= 500
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for PortfolioErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortfolioErrorKind::Network(message) => write!(f, "Network error: {message}"),
+            PortfolioErrorKind::Http {
+                status,
+                status_text,
+            } => write!(f, "HTTP error: {status} {status_text}"),
+            PortfolioErrorKind::InvalidUtf8 => f.write_str("Response body is not valid UTF-8"),
+            PortfolioErrorKind::Parse(diagnostic) => {
+                write!(f, "{} parse error: ", diagnostic.format)?;
+                if let Some(path) = &diagnostic.path {
+                    write!(f, "{path}: ")?;
+                }
+                write!(f, "{}", diagnostic.message)?;
+                if let Some(location) = diagnostic.location {
+                    write!(f, " at line {} column {}", location.line, location.column)?;
+                }
+                Ok(())
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            PortfolioErrorKind::Io(message) => write!(f, "File read error: {message}"),
+        }
+    }
+}
+
+/// Failure to load one portfolio source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PortfolioError {
+    pub(crate) source: PortfolioSource,
+    pub(crate) kind: PortfolioErrorKind,
+}
+
+impl PortfolioError {
+    pub(crate) fn new(source: PortfolioSource, kind: PortfolioErrorKind) -> Self {
+        Self { source, kind }
+    }
+
+    /// Short Japanese description shown in the error banner.
+    pub(crate) fn summary(&self) -> String {
+        match &self.kind {
+            PortfolioErrorKind::Network(_) => "ネットワークに接続できません".to_owned(),
+            PortfolioErrorKind::Http { status: 404, .. } => {
+                "ポートフォリオが見つかりません (404)".to_owned()
+            }
+            PortfolioErrorKind::Http { status, .. } if *status >= 500 => {
+                format!("サーバーエラーが発生しました ({status})")
+            }
+            PortfolioErrorKind::Http { status, .. } => {
+                format!("ポートフォリオを取得できません ({status})")
+            }
+            PortfolioErrorKind::InvalidUtf8 => "データが UTF-8 ではありません".to_owned(),
+            PortfolioErrorKind::Parse(diagnostic) => match diagnostic.location {
+                Some(location) => format!(
+                    "{} の形式が正しくありません（{}行 {}列）",
+                    diagnostic.format, location.line, location.column
+                ),
+                None => format!("{} の形式が正しくありません", diagnostic.format),
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            PortfolioErrorKind::Io(_) => "ファイルを読み込めません".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for PortfolioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.kind)
+    }
+}
+
+impl std::error::Error for PortfolioError {}
+
+/// 1-based line and character column of the byte `offset` in `text`.
+fn location_of(text: &str, offset: usize) -> TextLocation {
+    let before = text.get(..offset).unwrap_or(text);
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    TextLocation {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+/// Lines within [`EXCERPT_RADIUS`] of the 1-based `line`.
+fn excerpt(text: &str, line: usize) -> Vec<ExcerptLine> {
+    let first = line.saturating_sub(EXCERPT_RADIUS).max(1);
+    text.lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .skip(first - 1)
+        .take_while(|(number, _)| *number <= line + EXCERPT_RADIUS)
+        .map(|(number, text)| ExcerptLine {
+            number,
+            text: text.to_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_yaml_keeps_location() {
+        let text = "- ok\n- [broken\n";
+        let err = serde_yaml::from_str::<Vec<String>>(text)
+            .expect_err("unterminated flow sequence should fail");
+        let PortfolioErrorKind::Parse(diagnostic) = PortfolioErrorKind::from_yaml(&err, text)
+        else {
+            panic!("YAML errors should map to the Parse kind");
+        };
+        let location = diagnostic.location.expect("syntax errors carry a location");
+        assert!(
+            location.line >= 2,
+            "error is on or after line 2: {location:?}"
+        );
+    }
+
+    #[test]
+    fn only_network_and_server_errors_are_transient() {
+        let http = |status| PortfolioErrorKind::Http {
+            status,
+            status_text: String::new(),
+        };
+        assert!(PortfolioErrorKind::Network("timeout".to_owned()).is_transient());
+        assert!(http(503).is_transient());
+        assert!(http(429).is_transient());
+        assert!(!http(404).is_transient());
+        assert!(!PortfolioErrorKind::InvalidUtf8.is_transient());
+    }
+
+    #[test]
+    fn display_includes_source() {
+        let err = PortfolioError::new(
+            PortfolioSource::Url("https://example.com/p.yaml".to_owned()),
+            PortfolioErrorKind::Http {
+                status: 404,
+                status_text: "Not Found".to_owned(),
+            },
+        );
+        assert_eq!(
+            err.to_string(),
+            "https://example.com/p.yaml: HTTP error: 404 Not Found"
+        );
+        assert_eq!(err.summary(), "ポートフォリオが見つかりません (404)");
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        name: String,
+        repo_url: String,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Section {
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn from_yaml_points_at_missing_field() {
+        let text = "\
+- items:
+    - name: A
+      repo_url: https://example.com/a
+- items:
+    - name: B
+      repo_url: https://example.com/b
+    - name: C
+";
+        let err = serde_yaml::from_str::<Vec<Section>>(text).expect_err("item C lacks repo_url");
+        let PortfolioErrorKind::Parse(diagnostic) = PortfolioErrorKind::from_yaml(&err, text)
+        else {
+            panic!("YAML errors should map to the Parse kind");
+        };
+        let ParseDiagnostic {
+            message,
+            location,
+            path,
+            excerpt,
+            ..
+        } = *diagnostic;
+        assert_eq!(message, "missing field `repo_url`");
+        assert_eq!(path.as_deref(), Some("[1].items[1].repo_url"));
+
+        let location = location.expect("missing fields carry a location");
+        let offending = excerpt
+            .iter()
+            .find(|line| line.number == location.line)
+            .expect("excerpt should include the offending line");
+        assert!(offending.text.contains("name: C"), "{offending:?}");
+    }
+
+    #[test]
+    fn location_of_counts_characters() {
+        let text = "name = \"日本語\"\ntags = [1,";
+        assert_eq!(
+            location_of(text, text.find("tags").unwrap()),
+            TextLocation { line: 2, column: 1 }
+        );
+        assert_eq!(
+            location_of(text, text.find('"').unwrap() + 1 + "日本".len()),
+            TextLocation {
+                line: 1,
+                column: 11
+            }
+        );
+    }
+
+    #[test]
+    fn excerpt_is_clamped_to_document() {
+        let text = "one\ntwo\nthree\nfour\nfive\nsix";
+        let numbers = |line| {
+            excerpt(text, line)
+                .iter()
+                .map(|l| l.number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(1), [1, 2, 3]);
+        assert_eq!(numbers(4), [2, 3, 4, 5, 6]);
+        assert_eq!(numbers(6), [4, 5, 6]);
+    }
+}
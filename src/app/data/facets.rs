@@ -0,0 +1,262 @@
+//! Badge facets: how many matching items carry each badge, and the badges
+//! picked in the facet panel.
+//!
+//! Badges are compared after [folding](super::fold), so `Rust`, `rust` and
+//! `ＲＵＳＴ` count as one facet, shown as first written in the data.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::fold::fold;
+use super::tag::TagRegistry;
+use super::RepoSummary;
+
+/// How several selected badges combine.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) enum TagMatch {
+    /// Items carrying every selected badge.
+    #[default]
+    All,
+    /// Items carrying at least one selected badge.
+    Any,
+}
+
+impl TagMatch {
+    pub(crate) const ALL: [TagMatch; 2] = [TagMatch::All, TagMatch::Any];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            TagMatch::All => "すべて含む",
+            TagMatch::Any => "いずれかを含む",
+        }
+    }
+}
+
+/// Badges selected in the facet panel, persisted with the app state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub(crate) struct TagSelection {
+    /// Folded badges.
+    tags: BTreeSet<String>,
+    pub(crate) mode: TagMatch,
+}
+
+impl TagSelection {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub(crate) fn is_selected(&self, key: &str) -> bool {
+        self.tags.contains(key)
+    }
+
+    /// Selects or deselects the badge with the folded text `key`.
+    pub(crate) fn toggle(&mut self, key: &str) {
+        if !self.tags.remove(key) {
+            self.tags.insert(key.to_owned());
+        }
+    }
+
+    /// Selects `badge` as written in the data, e.g. from a clicked chip.
+    pub(crate) fn add_badge(&mut self, badge: &str) {
+        let key = fold(badge.trim());
+        if !key.is_empty() {
+            self.tags.insert(key);
+        }
+    }
+
+    /// Folded texts of the selected badges, in sorted order.
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.tags.clear();
+    }
+
+    /// Replaces aliases among the selected badges by their canonical tags,
+    /// e.g. for a selection saved before the alias was registered.
+    pub(crate) fn resolve(&mut self, registry: &TagRegistry) {
+        self.tags = self
+            .tags
+            .iter()
+            .map(|key| registry.resolve(key).key().to_owned())
+            .collect();
+    }
+
+    /// Whether `repo` passes the selection; everything passes an empty one.
+    pub(crate) fn matches(&self, repo: &RepoSummary) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+        let carries = |key: &String| repo.badges.iter().any(|badge| badge.key() == key);
+        match self.mode {
+            TagMatch::All => self.tags.iter().all(carries),
+            TagMatch::Any => self.tags.iter().any(carries),
+        }
+    }
+}
+
+/// One badge of the facet panel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TagCount {
+    /// Folded badge, as passed to [`TagSelection::toggle`].
+    pub(crate) key: String,
+    pub(crate) label: String,
+    pub(crate) count: usize,
+}
+
+/// Counts the badges of `repos`, most common first. Selected badges are
+/// listed even when no item carries them, so they can still be deselected;
+/// those are labelled with their name in `registry`, if any.
+pub(crate) fn count_tags<'a>(
+    repos: impl IntoIterator<Item = &'a RepoSummary>,
+    selection: &TagSelection,
+    registry: &TagRegistry,
+) -> Vec<TagCount> {
+    let mut counts: HashMap<String, TagCount> = HashMap::new();
+    for repo in repos {
+        for badge in &repo.badges {
+            counts
+                .entry(badge.key().to_owned())
+                .or_insert_with(|| TagCount {
+                    key: badge.key().to_owned(),
+                    label: badge.label().to_owned(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+    for key in &selection.tags {
+        counts.entry(key.clone()).or_insert_with(|| TagCount {
+            key: key.clone(),
+            label: registry.resolve(key).label().to_owned(),
+            count: 0,
+        });
+    }
+    let mut counts: Vec<TagCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Tag;
+    use super::*;
+
+    fn repo(badges: &str) -> RepoSummary {
+        RepoSummary {
+            name: "repo".to_string(),
+            description: String::new(),
+            image_url: None,
+            repo_url: "https://example.com".to_string(),
+            badges: Tag::split(badges),
+            updated_at: None,
+        }
+    }
+
+    fn selection(tags: &[&str], mode: TagMatch) -> TagSelection {
+        let mut selection = TagSelection {
+            mode,
+            ..TagSelection::default()
+        };
+        for tag in tags {
+            selection.toggle(tag);
+        }
+        selection
+    }
+
+    #[test]
+    fn counts_folded_badges_most_common_first() {
+        let repos = [
+            repo("Rust, egui"),
+            repo("rust, Python"),
+            repo("ＲＵＳＴ, rust, egui"),
+        ];
+        let counts: Vec<(String, usize)> =
+            count_tags(&repos, &TagSelection::default(), &TagRegistry::default())
+                .into_iter()
+                .map(|tag| (tag.label, tag.count))
+                .collect();
+        assert_eq!(
+            counts,
+            [
+                ("Rust".to_string(), 3),
+                ("egui".to_string(), 2),
+                ("Python".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn selected_badges_are_listed_without_matches() {
+        let registry: TagRegistry =
+            serde_yaml::from_str("- name: WebAssembly\n  aliases: [WASM]\n").unwrap();
+        let selected = selection(&["go", "webassembly"], TagMatch::All);
+        let counts = count_tags(&[repo("egui")], &selected, &registry);
+        let listed: Vec<(&str, &str, usize)> = counts
+            .iter()
+            .map(|tag| (tag.key.as_str(), tag.label.as_str(), tag.count))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("egui", "egui", 1),
+                ("go", "go", 0),
+                ("webassembly", "WebAssembly", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn saved_aliases_resolve_to_canonical_keys() {
+        let registry: TagRegistry =
+            serde_yaml::from_str("- name: WebAssembly\n  aliases: [WASM]\n").unwrap();
+        let mut saved = selection(&["wasm", "go"], TagMatch::Any);
+        saved.resolve(&registry);
+        assert_eq!(saved.keys().collect::<Vec<_>>(), ["go", "webassembly"]);
+        assert!(saved.matches(&RepoSummary {
+            badges: vec![registry.resolve("WASM")],
+            ..repo("")
+        }));
+    }
+
+    #[test]
+    fn all_requires_every_tag_and_any_one_of_them() {
+        let both = repo("Rust, egui");
+        let rust = repo("rust");
+        let all = selection(&["rust", "egui"], TagMatch::All);
+        assert!(all.matches(&both));
+        assert!(!all.matches(&rust));
+
+        let any = selection(&["rust", "egui"], TagMatch::Any);
+        assert!(any.matches(&both));
+        assert!(any.matches(&rust));
+        assert!(!any.matches(&repo("python")));
+
+        assert!(TagSelection::default().matches(&repo("")));
+    }
+
+    #[test]
+    fn badges_are_added_folded() {
+        let mut selection = TagSelection::default();
+        selection.add_badge(" ＲＵＳＴ ");
+        selection.add_badge("Rust");
+        selection.add_badge("");
+        assert_eq!(selection.keys().collect::<Vec<_>>(), ["rust"]);
+        assert!(selection.matches(&repo("rust")));
+    }
+
+    #[test]
+    fn toggle_adds_and_removes() {
+        let mut selection = TagSelection::default();
+        selection.toggle("rust");
+        assert!(selection.is_selected("rust"));
+        assert_eq!(selection.len(), 1);
+        selection.toggle("rust");
+        assert!(selection.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+//! HTTP access behind a trait so loaders can be driven by fake responses in tests.
+//!
+//! Only the transport is pluggable: [`PortfolioSource`](crate::app::config::PortfolioSource)
+//! stays a closed enum because it is configuration data, parsed from and
+//! written back as strings. URL sources go through a [`Fetcher`]; local files
+//! are read directly and tested against temporary files.
+
+/// Callback that receives the outcome of a single request.
+pub(crate) type FetchCallback = Box<dyn FnOnce(ehttp::Result<ehttp::Response>) + Send>;
+
+/// Performs HTTP requests for the portfolio loader.
+pub(crate) trait Fetcher: Send + Sync + 'static {
+    /// Sends `request` and calls `on_done` once, possibly from another thread.
+    fn fetch(&self, request: ehttp::Request, on_done: FetchCallback);
+}
+
+/// Fetcher backed by `ehttp`.
+pub(crate) struct HttpFetcher;
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, request: ehttp::Request, on_done: FetchCallback) {
+        ehttp::fetch(request, on_done);
+    }
+}
+
+#[cfg(test)]
+pub(crate) use memory::MemoryFetcher;
+
+#[cfg(test)]
+mod memory {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use super::{FetchCallback, Fetcher};
+
+    /// In-memory fetcher that answers from canned responses.
+    ///
+    /// Requests are queued until [`MemoryFetcher::complete_next`] is called, so
+    /// tests decide exactly when each response arrives and can observe the
+    /// loader while it is still loading. URLs without a canned response get 404.
+    #[derive(Default)]
+    pub(crate) struct MemoryFetcher {
+        responses: Mutex<HashMap<String, ehttp::Result<ehttp::Response>>>,
+        pending: Mutex<Vec<(ehttp::Request, FetchCallback)>>,
+    }
+
+    impl Fetcher for MemoryFetcher {
+        fn fetch(&self, request: ehttp::Request, on_done: FetchCallback) {
+            self.pending.lock().unwrap().push((request, on_done));
+        }
+    }
+
+    impl MemoryFetcher {
+        /// Answers requests for `url` with `status` and `body`.
+        pub(crate) fn respond(&self, url: &str, status: u16, body: impl Into<Vec<u8>>) {
+            self.respond_with(url, Ok(response(url, status, body.into())));
+        }
+
+        /// Answers requests for `url` with `result`, e.g. a network error.
+        pub(crate) fn respond_with(&self, url: &str, result: ehttp::Result<ehttp::Response>) {
+            self.responses
+                .lock()
+                .unwrap()
+                .insert(url.to_owned(), result);
+        }
+
+        /// URLs of the requests that have not been answered yet, oldest first.
+        pub(crate) fn pending_urls(&self) -> Vec<String> {
+            self.pending
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(request, _)| request.url.clone())
+                .collect()
+        }
+
+        /// Removes the oldest pending request without answering it, so a test can
+        /// answer it later, e.g. after newer requests.
+        pub(crate) fn take_next(&self) -> Option<(ehttp::Request, FetchCallback)> {
+            let mut pending = self.pending.lock().unwrap();
+            (!pending.is_empty()).then(|| pending.remove(0))
+        }
+
+        /// Answers the oldest pending request. Returns `false` if none is pending.
+        pub(crate) fn complete_next(&self) -> bool {
+            // コールバック内で再度リクエストされてもデッドロックしないよう先に取り出す
+            let Some((request, on_done)) = self.take_next() else {
+                return false;
+            };
+            let result = self
+                .responses
+                .lock()
+                .unwrap()
+                .get(&request.url)
+                .cloned()
+                .unwrap_or_else(|| Ok(response(&request.url, 404, Vec::new())));
+            on_done(result);
+            true
+        }
+
+        /// Answers every pending request, including ones issued while answering.
+        pub(crate) fn complete_all(&self) {
+            while self.complete_next() {}
+        }
+    }
+
+    /// Builds a response the way `ehttp` would report it.
+    fn response(url: &str, status: u16, bytes: Vec<u8>) -> ehttp::Response {
+        ehttp::Response {
+            url: url.to_owned(),
+            ok: (200..300).contains(&status),
+            status,
+            status_text: match status {
+                200 => "OK",
+                304 => "Not Modified",
+                404 => "Not Found",
+                500 => "Internal Server Error",
+                503 => "Service Unavailable",
+                _ => "",
+            }
+            .to_owned(),
+            headers: ehttp::Headers::default(),
+            bytes,
+        }
+    }
+}
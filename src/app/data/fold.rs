@@ -0,0 +1,99 @@
+//! Text folding shared by the search index and the matcher.
+//!
+//! Portfolio text is mostly Japanese, so besides ignoring case, folding maps
+//! each character through Unicode NFKC (full-width `ＲＵＳＴ` becomes `rust`,
+//! half-width `ﾗｽﾄ` becomes `ラスト`) and then hiragana onto katakana, so that
+//! `らすと` matches `ラスト` as well.
+
+use unicode_normalization::char::{compose, is_combining_mark};
+use unicode_normalization::UnicodeNormalization as _;
+
+/// Folded characters of `text`, each with the byte offset of the original
+/// character it came from. One character may fold into several (`㌔` to
+/// `キロ`), and a half-width sound mark is merged into the kana before it.
+pub(crate) fn fold_chars(text: &str) -> Vec<(usize, char)> {
+    let mut folded: Vec<(usize, char)> = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        if c.is_ascii() {
+            folded.push((offset, c.to_ascii_lowercase()));
+            continue;
+        }
+        for normalized in std::iter::once(c).nfkc() {
+            // 半角の濁点・半濁点は NFKC で結合文字になるので、前の文字と合成する（ｶﾞ → ガ）
+            if is_combining_mark(normalized) {
+                if let Some(last) = folded.last_mut() {
+                    if let Some(composed) = compose(last.1, normalized) {
+                        last.1 = composed;
+                        continue;
+                    }
+                }
+            }
+            for lower in normalized.to_lowercase() {
+                folded.push((offset, katakana(lower)));
+            }
+        }
+    }
+    folded
+}
+
+/// `text` folded for comparison, see [`fold_chars`].
+pub(crate) fn fold(text: &str) -> String {
+    fold_chars(text).into_iter().map(|(_, c)| c).collect()
+}
+
+/// Maps hiragana (including the iteration marks ゝ and ゞ) onto katakana.
+fn katakana(c: char) -> char {
+    match c {
+        '\u{3041}'..='\u{3096}' | '\u{309D}' | '\u{309E}' => {
+            char::from_u32(c as u32 + 0x60).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn japanese_variants_fold_to_the_same_text() {
+        let cases = [
+            // 全角・半角英数字
+            ("ＲＵＳＴ", "rust"),
+            ("Ｒｕｓｔ　２０２４", "rust 2024"),
+            // 半角カナ（濁点・半濁点つきを含む）
+            ("ﾗｽﾄ", "ラスト"),
+            ("ｶﾞｲﾄﾞ", "ガイド"),
+            ("ﾊﾟﾈﾙ", "パネル"),
+            ("ｳﾞｨｭｰｱｰ", "ヴィューアー"),
+            // ひらがなとカタカナ
+            ("らすと", "ラスト"),
+            ("がいど", "ガイド"),
+            ("ぱねる", "パネル"),
+            ("ゔぁ", "ヴァ"),
+            ("いすゞ", "イスヾ"),
+            // 互換文字
+            ("㌔", "キロ"),
+            ("①", "1"),
+            // 漢字や記号はそのまま
+            ("地図ビューア", "地図ビューア"),
+            ("C++ / Web", "c++ / web"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(fold(input), fold(expected), "{input} vs {expected}");
+        }
+        assert_eq!(fold("ﾗｽﾄ"), "ラスト");
+        assert_eq!(fold("らすと"), "ラスト");
+    }
+
+    #[test]
+    fn folded_chars_keep_original_offsets() {
+        let text = "aｶﾞ㌔";
+        let folded = fold_chars(text);
+        assert_eq!(
+            folded,
+            [(0, 'a'), (1, 'ガ'), (7, 'キ'), (7, 'ロ')],
+            "the sound mark merges into the kana at offset 1"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Serialization formats accepted for portfolio documents.
+//!
+//! The same `RepoSection` / `FeaturedRepo` structs are read from YAML, JSON or
+//! TOML. The format is taken from the `Content-Type` header when it names one,
+//! then from the file extension, and finally guessed from the content itself.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+
+use super::PortfolioErrorKind;
+
+/// Document format of a portfolio source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PortfolioFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl PortfolioFormat {
+    /// Picks the format from, in order, the media type, the extension of
+    /// `location` (a URL or path) and the document text.
+    pub(crate) fn detect(content_type: Option<&str>, location: &str, text: &str) -> Self {
+        content_type
+            .and_then(PortfolioFormat::from_content_type)
+            .or_else(|| PortfolioFormat::from_extension(location))
+            .unwrap_or_else(|| PortfolioFormat::sniff(text))
+    }
+
+    /// Format named by a `Content-Type` value, ignoring generic types like `text/plain`.
+    pub(crate) fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if media_type.ends_with("/json") || media_type.ends_with("+json") {
+            Some(PortfolioFormat::Json)
+        } else if media_type.ends_with("/toml") {
+            Some(PortfolioFormat::Toml)
+        } else if media_type.ends_with("/yaml") || media_type.ends_with("/x-yaml") {
+            Some(PortfolioFormat::Yaml)
+        } else {
+            None
+        }
+    }
+
+    /// Format implied by the extension of a URL or path, ignoring any query or fragment.
+    pub(crate) fn from_extension(location: &str) -> Option<Self> {
+        let path = location.split(['?', '#']).next().unwrap_or_default();
+        let file_name = path.rsplit(['/', '\\']).next().unwrap_or_default();
+        let (_, extension) = file_name.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(PortfolioFormat::Yaml),
+            "json" => Some(PortfolioFormat::Json),
+            "toml" => Some(PortfolioFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the first meaningful line, defaulting to YAML.
+    pub(crate) fn sniff(text: &str) -> Self {
+        let Some(line) = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+        else {
+            return PortfolioFormat::Yaml;
+        };
+
+        if is_toml_table_header(line) || is_toml_key_value(line) {
+            PortfolioFormat::Toml
+        } else if line.starts_with('{') || line.starts_with('[') {
+            PortfolioFormat::Json
+        } else {
+            PortfolioFormat::Yaml
+        }
+    }
+
+    /// Deserializes `text`, mapping failures to a diagnostic that points into it.
+    pub(crate) fn deserialize<T: DeserializeOwned>(
+        self,
+        text: &str,
+    ) -> Result<T, PortfolioErrorKind> {
+        match self {
+            PortfolioFormat::Yaml => {
+                serde_yaml::from_str(text).map_err(|e| PortfolioErrorKind::from_yaml(&e, text))
+            }
+            PortfolioFormat::Json => {
+                serde_json::from_str(text).map_err(|e| PortfolioErrorKind::from_json(&e, text))
+            }
+            PortfolioFormat::Toml => {
+                toml::from_str(text).map_err(|e| PortfolioErrorKind::from_toml(&e, text))
+            }
+        }
+    }
+}
+
+impl fmt::Display for PortfolioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PortfolioFormat::Yaml => "YAML",
+            PortfolioFormat::Json => "JSON",
+            PortfolioFormat::Toml => "TOML",
+        })
+    }
+}
+
+/// `[table]` or `[[array.of.tables]]` with a bare or dotted key.
+fn is_toml_table_header(line: &str) -> bool {
+    let inner = line
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .or_else(|| {
+            line.strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+        });
+    inner.is_some_and(|key| {
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' '))
+    })
+}
+
+/// `key = value`, which YAML would write as `key: value`.
+fn is_toml_key_value(line: &str) -> bool {
+    line.split_once('=').is_some_and(|(key, _)| {
+        let key = key.trim();
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_type_wins_over_extension() {
+        assert_eq!(
+            PortfolioFormat::detect(
+                Some("application/json; charset=utf-8"),
+                "https://example.com/portfolio.yaml",
+                "- name: x",
+            ),
+            PortfolioFormat::Json
+        );
+        // 汎用的な型は判断材料にせず拡張子を見る
+        assert_eq!(
+            PortfolioFormat::detect(
+                Some("text/plain"),
+                "https://example.com/portfolio.toml?v=2",
+                "",
+            ),
+            PortfolioFormat::Toml
+        );
+    }
+
+    #[test]
+    fn extension_is_read_from_urls_and_paths() {
+        let cases = [
+            (
+                "https://example.com/data/portfolio.json#top",
+                Some(PortfolioFormat::Json),
+            ),
+            ("./assets/portfolio.YML", Some(PortfolioFormat::Yaml)),
+            ("C:\\data\\portfolio.toml", Some(PortfolioFormat::Toml)),
+            ("https://example.com/portfolio", None),
+            ("https://example.com/v1.2/portfolio", None),
+        ];
+        for (location, expected) in cases {
+            assert_eq!(
+                PortfolioFormat::from_extension(location),
+                expected,
+                "{location}"
+            );
+        }
+    }
+
+    #[test]
+    fn sniff_recognizes_each_format() {
+        let cases = [
+            ("- name: Tools\n  items: []", PortfolioFormat::Yaml),
+            (
+                "# comment\nsections:\n  - name: Tools",
+                PortfolioFormat::Yaml,
+            ),
+            ("[\n  {\"name\": \"Tools\"}\n]", PortfolioFormat::Json),
+            ("{\"sections\": []}", PortfolioFormat::Json),
+            (
+                "# comment\n[[sections]]\nname = \"Tools\"",
+                PortfolioFormat::Toml,
+            ),
+            ("title = \"Portfolio\"", PortfolioFormat::Toml),
+            ("", PortfolioFormat::Yaml),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(PortfolioFormat::sniff(text), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn deserialize_errors_carry_locations() {
+        for (format, text) in [
+            (PortfolioFormat::Yaml, "a: [1,\nb"),
+            (PortfolioFormat::Json, "{\n  \"a\": [1,\n}"),
+            (PortfolioFormat::Toml, "a = 1\nb = [1,\n"),
+        ] {
+            let err = format
+                .deserialize::<serde_yaml::Value>(text)
+                .expect_err("document is malformed");
+            let PortfolioErrorKind::Parse(diagnostic) = err else {
+                panic!("{format} errors should map to the Parse kind");
+            };
+            assert_eq!(diagnostic.format, format);
+            assert!(diagnostic.location.is_some(), "{format}: {diagnostic:?}");
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Typo-tolerant matching of query words against repository text.
+//!
+//! Text is compared character by character after [folding](super::fold), keeping the
+//! byte offset of every character so matches can be highlighted in the
+//! original string. A query word matches a field when it occurs in it, or when
+//! a word of the field (or the start of one) is within a small edit distance.
+
+use std::ops::Range;
+
+use super::fold::fold_chars;
+
+/// Folded characters of a text together with their byte offsets in the original.
+pub(crate) struct FoldedText<'a> {
+    text: &'a str,
+    chars: Vec<(usize, char)>,
+}
+
+impl<'a> FoldedText<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            chars: fold_chars(text),
+        }
+    }
+
+    /// Byte range in the original text covered by the characters `start..end`.
+    fn byte_range(&self, start: usize, end: usize) -> Range<usize> {
+        let byte_start = self.chars[start].0;
+        let byte_end = self
+            .chars
+            .get(end..)
+            .and_then(|rest| rest.iter().find(|(offset, _)| *offset > byte_start))
+            .map_or(self.text.len(), |(offset, _)| *offset);
+        byte_start..byte_end
+    }
+
+    /// Alphanumeric runs as `(start, end)` character indices.
+    fn words(&self) -> Vec<(usize, usize)> {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (index, (_, c)) in self.chars.iter().enumerate() {
+            match (c.is_alphanumeric(), start) {
+                (true, None) => start = Some(index),
+                (false, Some(word_start)) => {
+                    words.push((word_start, index));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(word_start) = start {
+            words.push((word_start, self.chars.len()));
+        }
+        words
+    }
+
+    fn is_word_start(&self, index: usize) -> bool {
+        index == 0 || !self.chars[index - 1].1.is_alphanumeric()
+    }
+}
+
+/// How well a query word matched a field; higher is better.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum MatchQuality {
+    /// Within the edit distance allowed for the word, with `1` or `2` edits.
+    Typo2,
+    Typo1,
+    /// Occurs inside a word.
+    Infix,
+    /// Occurs at the start of a word.
+    WordStart,
+}
+
+impl MatchQuality {
+    pub(crate) fn points(self) -> u32 {
+        match self {
+            MatchQuality::Typo2 => 1,
+            MatchQuality::Typo1 => 2,
+            MatchQuality::Infix => 3,
+            MatchQuality::WordStart => 4,
+        }
+    }
+}
+
+/// Best quality of `query` (folded) in `text`, with the byte ranges of every occurrence.
+pub(crate) fn match_word(
+    text: &FoldedText<'_>,
+    query: &[char],
+) -> Option<(MatchQuality, Vec<Range<usize>>)> {
+    // まず部分一致を探し、なければ誤字として許容できる単語を探す
+    if let Some(found) = match_exact(text, query) {
+        return Some(found);
+    }
+
+    let mut best = None;
+    let mut ranges = Vec::new();
+    for (start, end) in text.words() {
+        let word: Vec<char> = text.chars[start..end].iter().map(|(_, c)| *c).collect();
+        if let Some((distance, len)) = typo_match(query, &word) {
+            let quality = if distance == 1 {
+                MatchQuality::Typo1
+            } else {
+                MatchQuality::Typo2
+            };
+            best = best.max(Some(quality));
+            ranges.push(text.byte_range(start, start + len));
+        }
+    }
+    best.map(|quality| (quality, ranges))
+}
+
+/// Occurrences of `query` (folded) in `text`, without typos.
+pub(crate) fn match_exact(
+    text: &FoldedText<'_>,
+    query: &[char],
+) -> Option<(MatchQuality, Vec<Range<usize>>)> {
+    if query.is_empty() || text.chars.is_empty() {
+        return None;
+    }
+
+    let mut best = None;
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index + query.len() <= text.chars.len() {
+        let found = text.chars[index..index + query.len()]
+            .iter()
+            .map(|(_, c)| *c)
+            .eq(query.iter().copied());
+        if found {
+            let quality = if text.is_word_start(index) {
+                MatchQuality::WordStart
+            } else {
+                MatchQuality::Infix
+            };
+            best = best.max(Some(quality));
+            ranges.push(text.byte_range(index, index + query.len()));
+            index += query.len();
+        } else {
+            index += 1;
+        }
+    }
+    best.map(|quality| (quality, ranges))
+}
+
+/// Edits tolerated for a query word of `len` characters.
+fn allowed_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Whether `word`, or its first characters, is within the allowed edit
+/// distance of `query`. Returns the distance and how many characters of `word` matched.
+///
+/// Typos are only looked for after a correct first character, which keeps a
+/// word from matching unrelated words that merely share its tail.
+pub(crate) fn typo_match(query: &[char], word: &[char]) -> Option<(usize, usize)> {
+    let allowed = allowed_typos(query.len());
+    if allowed == 0 || word.len() + allowed < query.len() || query.first() != word.first() {
+        return None;
+    }
+    // 単語全体、または入力途中を想定した同じ長さ前後の先頭部分と比べる
+    let longest = word.len().min(query.len() + allowed);
+    let shortest = query.len().saturating_sub(allowed).max(1);
+    let distances = prefix_distances(query, &word[..longest]);
+    (shortest..=longest)
+        .map(|len| (distances[len], len))
+        .filter(|&(distance, _)| distance <= allowed)
+        .min_by_key(|&(distance, len)| (distance, std::cmp::Reverse(len)))
+}
+
+/// Optimal string alignment distance between `a` and `b` as a whole.
+pub(crate) fn edit_distance(a: &[char], b: &[char]) -> usize {
+    prefix_distances(a, b)[b.len()]
+}
+
+/// Optimal string alignment distance from `a` to every prefix of `b`, indexed
+/// by prefix length. Insertions, deletions, substitutions and transpositions of
+/// adjacent characters each count as one edit.
+fn prefix_distances(a: &[char], b: &[char]) -> Vec<usize> {
+    let width = b.len() + 1;
+    let mut rows = vec![0; (a.len() + 1) * width];
+    for (j, cell) in rows.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        rows[i * width] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut value = (rows[(i - 1) * width + j] + 1)
+                .min(rows[i * width + j - 1] + 1)
+                .min(rows[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(rows[(i - 2) * width + j - 2] + 1);
+            }
+            rows[i * width + j] = value;
+        }
+    }
+    rows.split_off(a.len() * width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    fn matched<'a>(text: &'a str, query: &str) -> Option<(MatchQuality, Vec<&'a str>)> {
+        let folded = FoldedText::new(text);
+        match_word(&folded, &chars(query))
+            .map(|(quality, ranges)| (quality, ranges.into_iter().map(|r| &text[r]).collect()))
+    }
+
+    #[test]
+    fn substrings_match_case_insensitively_with_original_ranges() {
+        assert_eq!(
+            matched("Map Panels map", "map"),
+            Some((MatchQuality::WordStart, vec!["Map", "map"]))
+        );
+        assert_eq!(
+            matched("Roadmap", "map"),
+            Some((MatchQuality::Infix, vec!["map"]))
+        );
+        assert_eq!(
+            matched("地図ビューア", "ビュー"),
+            Some((MatchQuality::Infix, vec!["ビュー"]))
+        );
+    }
+
+    #[test]
+    fn width_and_kana_variants_match_with_original_ranges() {
+        assert_eq!(
+            matched("ＲＵＳＴ製のﾋﾞｭｰｱ", "rust"),
+            Some((MatchQuality::WordStart, vec!["ＲＵＳＴ"]))
+        );
+        assert_eq!(
+            matched("ＲＵＳＴ製のﾋﾞｭｰｱ", "ビュー"),
+            Some((MatchQuality::Infix, vec!["ﾋﾞｭｰ"]))
+        );
+        assert_eq!(
+            matched("ひらがなのガイド", "ヒラガナ"),
+            Some((MatchQuality::WordStart, vec!["ひらがな"]))
+        );
+    }
+
+    #[test]
+    fn typos_are_tolerated_by_word_length() {
+        assert_eq!(
+            matched("Tile renderer", "rendr"),
+            Some((MatchQuality::Typo1, vec!["render"]))
+        );
+        assert_eq!(
+            matched("WebAssembly", "webasembyl"),
+            Some((MatchQuality::Typo2, vec!["WebAssembly"]))
+        );
+        // 短い単語は誤字を許容しない
+        assert_eq!(matched("egui", "eui"), None);
+        assert_eq!(matched("Tile renderer", "python"), None);
+        assert_eq!(matched("guide", "egui"), None, "first character must match");
+    }
+
+    #[test]
+    fn transpositions_count_as_one_edit() {
+        let distance = |a: &str, b: &str| *prefix_distances(&chars(a), &chars(b)).last().unwrap();
+        assert_eq!(distance("rsut", "rust"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(
+            prefix_distances(&chars("map"), &chars("maps")),
+            [3, 2, 1, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,1373 @@
+mod cache;
+mod error;
+mod facets;
+mod fetch;
+mod fold;
+mod format;
+mod fuzzy;
+mod parse;
+mod query;
+mod retry;
+mod search;
+mod suggest;
+mod tag;
+mod view;
+#[cfg(not(target_arch = "wasm32"))]
+mod watch;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::config::PortfolioSource;
+use cache::CachedResponse;
+pub(crate) use cache::{describe_age, PortfolioCache};
+pub(crate) use error::{
+    ExcerptLine, ParseDiagnostic, PortfolioError, PortfolioErrorKind, TextLocation,
+};
+pub(crate) use facets::{TagCount, TagMatch, TagSelection};
+use fetch::{Fetcher, HttpFetcher};
+pub(crate) use format::PortfolioFormat;
+pub(crate) use parse::ParseWarning;
+use parse::{parse_portfolio, ParsedPortfolio};
+#[cfg(feature = "bench")]
+pub(crate) use query::Query;
+pub(crate) use query::QueryError;
+pub(crate) use retry::{jitter, RetryPolicy};
+use retry::{Clock, SystemClock};
+pub(crate) use search::SearchHit;
+#[cfg(feature = "bench")]
+pub(crate) use search::SearchIndex;
+pub(crate) use suggest::{SearchHistory, SearchSuggestions, Suggestion, SuggestionKind};
+pub(crate) use tag::{Tag, TagRegistry, TagStyle};
+pub(crate) use view::{FilteredSections, SectionView};
+#[cfg(not(target_arch = "wasm32"))]
+use watch::FileWatcher;
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
+pub(crate) struct RepoSummary {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) image_url: Option<String>,
+    pub(crate) repo_url: String,
+    /// リスト、または従来のカンマ区切り文字列として読み込む
+    #[serde(default, with = "tag::badges", skip_serializing_if = "Vec::is_empty")]
+    pub(crate) badges: Vec<Tag>,
+    #[serde(default)]
+    pub(crate) updated_at: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq, Default)]
+pub(crate) struct SectionMeta {
+    #[serde(rename = "rowKey", default)]
+    pub(crate) row_key: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
+pub(crate) struct RepoSection {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) meta: SectionMeta,
+    pub(crate) items: Vec<RepoSummary>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
+pub(crate) struct FeaturedRepo {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) repository: String,
+    pub(crate) tags: Vec<String>,
+    pub(crate) updated_at: String,
+    /// ヒーローセクションに表示する画像。未指定や読み込み失敗時は同梱のロゴを表示する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) image_url: Option<String>,
+}
+
+pub(crate) fn load_featured_repo() -> FeaturedRepo {
+    const FEATURED_YAML: &str = include_str!("../../../assets/featured.yaml");
+
+    PortfolioFormat::Yaml
+        .deserialize::<FeaturedRepo>(FEATURED_YAML)
+        .expect("featured YAML should be valid")
+}
+
+/// ポートフォリオデータのロード状態
+#[derive(Clone, Default)]
+pub(crate) enum PortfolioLoadState {
+    #[default]
+    NotStarted,
+    /// `attempt` 回目（1 始まり）の取得中
+    Loading { attempt: u32 },
+    /// 「リポジトリデータ更新」による再取得中。完了するまで前回のデータを表示する
+    Refreshing,
+    /// 読み込んだセクション。毎フレーム状態を取得しても複製されないよう共有する
+    Loaded(Arc<[RepoSection]>),
+    /// `attempt` 回目の取得に失敗。`retry_at` があればその時刻に自動で再試行する
+    Error {
+        error: PortfolioError,
+        attempt: u32,
+        retry_at: Option<Duration>,
+    },
+}
+
+type SourceResult = Result<ParsedPortfolio, PortfolioError>;
+
+/// ソースごとの取得結果を集約し、すべて揃った時点で状態を確定させる
+struct LoaderShared {
+    state: PortfolioLoadState,
+    results: Vec<Option<SourceResult>>,
+    cache: PortfolioCache,
+    /// 取得に失敗しキャッシュで代替したソースのエラー
+    fallback_errors: Vec<PortfolioError>,
+    /// 解釈できずに読み飛ばした項目（直近の結合結果のもの）
+    warnings: Vec<ParseWarning>,
+    /// ソースから読み込んだ注目リポジトリ（直近の結合結果のもの）
+    featured: Vec<FeaturedRepo>,
+    attempt: u32,
+    /// 試行を開始するたびに増える世代番号。古い世代の応答は破棄する
+    generation: u64,
+    /// 読み込んだ内容が変わるたびに増える版数
+    revision: u64,
+    /// 直近の試行が完了（成功または失敗）した時刻
+    settled_at: Duration,
+    policy: RetryPolicy,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for LoaderShared {
+    fn default() -> Self {
+        Self {
+            state: PortfolioLoadState::default(),
+            results: Vec::new(),
+            cache: PortfolioCache::default(),
+            fallback_errors: Vec::new(),
+            warnings: Vec::new(),
+            featured: Vec::new(),
+            attempt: 0,
+            generation: 0,
+            revision: 0,
+            settled_at: Duration::ZERO,
+            policy: RetryPolicy::default(),
+            clock: Arc::new(SystemClock::default()),
+        }
+    }
+}
+
+impl LoaderShared {
+    /// `index` 番目のソースの結果を記録し、全ソースが完了していれば宣言順に結合します
+    ///
+    /// 監視中のファイルが更新された場合も同じ経路で結果を差し替えます。
+    /// 一時的な失敗の場合は再試行ポリシーに従って次の試行時刻を決めます。
+    fn store_result(&mut self, index: usize, result: SourceResult) {
+        self.results[index] = Some(result);
+        if self.results.iter().any(Option::is_none) {
+            return;
+        }
+        self.settled_at = self.clock.now();
+
+        let mut sections = Vec::new();
+        let mut featured = Vec::new();
+        let mut warnings = Vec::new();
+        for result in self.results.iter().flatten() {
+            match result {
+                Ok(parsed) => {
+                    sections.extend_from_slice(&parsed.sections);
+                    featured.extend_from_slice(&parsed.featured);
+                    warnings.extend_from_slice(&parsed.warnings);
+                }
+                Err(err) => {
+                    let transient = self
+                        .results
+                        .iter()
+                        .flatten()
+                        .any(|result| matches!(result, Err(err) if err.kind.is_transient()));
+                    let retry_at =
+                        (transient && self.policy.should_retry(self.attempt)).then(|| {
+                            self.clock.now() + self.policy.delay(self.attempt, retry::jitter())
+                        });
+                    self.state = PortfolioLoadState::Error {
+                        error: err.clone(),
+                        attempt: self.attempt,
+                        retry_at,
+                    };
+                    return;
+                }
+            }
+        }
+        self.warnings = warnings;
+        let unchanged = featured == self.featured
+            && matches!(&self.state, PortfolioLoadState::Loaded(current) if **current == *sections);
+        if !unchanged {
+            self.revision += 1;
+        }
+        self.featured = featured;
+        self.state = PortfolioLoadState::Loaded(sections.into());
+    }
+
+    /// 次の試行を開始し、再取得が必要な（失敗した）ソースの番号を返します
+    fn begin_attempt(&mut self) -> Vec<usize> {
+        self.attempt += 1;
+        self.generation += 1;
+        self.state = PortfolioLoadState::Loading {
+            attempt: self.attempt,
+        };
+        let mut failed = Vec::new();
+        for (index, result) in self.results.iter_mut().enumerate() {
+            if !matches!(result, Some(Ok(_))) {
+                *result = None;
+                failed.push(index);
+            }
+        }
+        failed
+    }
+
+    /// すべてのソースを取り直す新しい試行を開始し、取得するソースの番号を返します
+    ///
+    /// 取得中の応答は世代が古くなるため、後から届いても状態を変更しません。
+    /// `background` の場合は完了するまで現在の状態（表示中のデータ）をそのまま保ちます。
+    fn begin_refresh(&mut self, background: bool) -> Vec<usize> {
+        let previous = std::mem::take(&mut self.state);
+        self.results.iter_mut().for_each(|result| *result = None);
+        self.fallback_errors.clear();
+        self.attempt = 0;
+        let indices = self.begin_attempt();
+        self.state = if background {
+            previous
+        } else {
+            PortfolioLoadState::Refreshing
+        };
+        indices
+    }
+
+    /// 自動更新までの残り時間。時刻になっていれば `Some(None)` を返します
+    ///
+    /// 読み込みが完了していない間や自動再試行を待っている間、ソースがない場合は `None` です。
+    fn auto_refresh_wait(&self, interval: Duration) -> Option<Option<Duration>> {
+        let settled = match self.state {
+            PortfolioLoadState::Loaded(_) => true,
+            PortfolioLoadState::Error { retry_at, .. } => retry_at.is_none(),
+            _ => false,
+        };
+        if !settled || self.results.is_empty() || self.results.iter().any(Option::is_none) {
+            return None;
+        }
+        let due = self.settled_at + interval;
+        Some(
+            due.checked_sub(self.clock.now())
+                .filter(|wait| !wait.is_zero()),
+        )
+    }
+
+    /// HTTP の取得結果を解釈します
+    ///
+    /// 成功時は本文と検証子をキャッシュし、304 ではキャッシュ済みの本文を使います。
+    /// 通信エラーや HTTP エラーの場合もキャッシュがあればそれで代替します。
+    fn resolve_response(
+        &mut self,
+        url: &str,
+        result: ehttp::Result<ehttp::Response>,
+        now: u64,
+    ) -> SourceResult {
+        let source = PortfolioSource::Url(url.to_owned());
+        let kind = match result {
+            Ok(response) if response.status == 304 => {
+                if let Some(parsed) = self
+                    .cache
+                    .get(url)
+                    .map(|cached| parse_portfolio(&source, &cached.body, cached.format(url)))
+                {
+                    self.cache.mark_revalidated(url, now);
+                    return parsed;
+                }
+                PortfolioErrorKind::Http {
+                    status: response.status,
+                    status_text: response.status_text,
+                }
+            }
+            Ok(response) if response.ok => {
+                let Some(text) = response.text() else {
+                    return Err(PortfolioError::new(source, PortfolioErrorKind::InvalidUtf8));
+                };
+                let format = PortfolioFormat::detect(response.content_type(), url, text);
+                let parsed = parse_portfolio(&source, text, format)?;
+                self.cache
+                    .insert(url, CachedResponse::from_response(&response, text, now));
+                return Ok(parsed);
+            }
+            Ok(response) => PortfolioErrorKind::Http {
+                status: response.status,
+                status_text: response.status_text,
+            },
+            Err(e) => PortfolioErrorKind::Network(e),
+        };
+
+        let fallback = self
+            .cache
+            .get(url)
+            .map(|cached| parse_portfolio(&source, &cached.body, cached.format(url)));
+        let error = PortfolioError::new(source, kind);
+        match fallback {
+            Some(Ok(parsed)) => {
+                log::warn!("{error}; showing cached data");
+                self.fallback_errors.push(error);
+                Ok(parsed)
+            }
+            _ => Err(error),
+        }
+    }
+}
+
+/// 非同期でポートフォリオデータを取得するためのハンドラ
+///
+/// HTTP の取得処理 `F` を差し替えられるため、テストでは応答を順に与えて状態遷移を検証できます。
+pub(crate) struct PortfolioLoader<F = HttpFetcher> {
+    sources: Vec<PortfolioSource>,
+    shared: Arc<Mutex<LoaderShared>>,
+    fetcher: Arc<F>,
+}
+
+impl<F> Clone for PortfolioLoader<F> {
+    fn clone(&self) -> Self {
+        Self {
+            sources: self.sources.clone(),
+            shared: self.shared.clone(),
+            fetcher: self.fetcher.clone(),
+        }
+    }
+}
+
+impl Default for PortfolioLoader {
+    fn default() -> Self {
+        PortfolioLoader::new(Vec::new(), PortfolioCache::default())
+    }
+}
+
+impl PortfolioLoader {
+    pub(crate) fn new(sources: Vec<PortfolioSource>, cache: PortfolioCache) -> Self {
+        PortfolioLoader::with_backends(
+            sources,
+            cache,
+            Arc::new(HttpFetcher),
+            Arc::new(SystemClock::default()),
+        )
+    }
+}
+
+impl<F: Fetcher> PortfolioLoader<F> {
+    /// 取得処理と時計を差し替えてローダーを作成します
+    pub(crate) fn with_backends(
+        sources: Vec<PortfolioSource>,
+        mut cache: PortfolioCache,
+        fetcher: Arc<F>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let urls: Vec<&str> = sources.iter().filter_map(PortfolioSource::url).collect();
+        cache.retain_urls(&urls);
+
+        Self {
+            sources,
+            shared: Arc::new(Mutex::new(LoaderShared {
+                cache,
+                clock,
+                ..Default::default()
+            })),
+            fetcher,
+        }
+    }
+
+    /// 現在の状態を取得します
+    pub(crate) fn state(&self) -> PortfolioLoadState {
+        self.shared.lock().unwrap().state.clone()
+    }
+
+    /// 読み込んだ内容の版数。内容が変わったときだけ増えます
+    pub(crate) fn revision(&self) -> u64 {
+        self.shared.lock().unwrap().revision
+    }
+
+    /// ソースから読み込んだ注目リポジトリ。どのソースにもなければ空です
+    pub(crate) fn featured(&self) -> Vec<FeaturedRepo> {
+        self.shared.lock().unwrap().featured.clone()
+    }
+
+    /// 永続化用に現在のキャッシュを取得します
+    pub(crate) fn cache(&self) -> PortfolioCache {
+        self.shared.lock().unwrap().cache.clone()
+    }
+
+    /// キャッシュ済みデータの経過時間（最も古いもの）
+    pub(crate) fn cache_age(&self) -> Option<Duration> {
+        self.shared.lock().unwrap().cache.age(cache::unix_now())
+    }
+
+    /// 取得に失敗しキャッシュで代替したソースのエラー一覧
+    pub(crate) fn fallback_errors(&self) -> Vec<PortfolioError> {
+        self.shared.lock().unwrap().fallback_errors.clone()
+    }
+
+    /// 解釈できずに読み飛ばしたセクションや項目の一覧
+    pub(crate) fn warnings(&self) -> Vec<ParseWarning> {
+        self.shared.lock().unwrap().warnings.clone()
+    }
+
+    /// 自動再試行までの残り時間
+    pub(crate) fn time_until_retry(&self) -> Option<Duration> {
+        let shared = self.shared.lock().unwrap();
+        match shared.state {
+            PortfolioLoadState::Error {
+                retry_at: Some(retry_at),
+                ..
+            } => Some(retry_at.saturating_sub(shared.clock.now())),
+            _ => None,
+        }
+    }
+
+    /// 設定されたすべてのソースからポートフォリオデータの取得を開始します
+    ///
+    /// 毎フレーム呼び出され、失敗後は再試行時刻になった時点で失敗したソースを再取得します。
+    pub(crate) fn start_loading(&self, ctx: &egui::Context) {
+        let indices = {
+            let mut shared = self.shared.lock().unwrap();
+            match shared.state {
+                PortfolioLoadState::NotStarted => {
+                    if self.sources.is_empty() {
+                        shared.state = PortfolioLoadState::Loaded(Arc::default());
+                        return;
+                    }
+                    shared.results = vec![None; self.sources.len()];
+                    // 読み込み中の変更を取りこぼさないよう、読み込み前に監視を開始する
+                    #[cfg(not(target_arch = "wasm32"))]
+                    self.watch_paths(ctx);
+                    shared.begin_attempt()
+                }
+                PortfolioLoadState::Error {
+                    retry_at: Some(retry_at),
+                    ..
+                } => {
+                    let now = shared.clock.now();
+                    if now < retry_at {
+                        ctx.request_repaint_after(retry_at - now);
+                        return;
+                    }
+                    shared.begin_attempt()
+                }
+                _ => return,
+            }
+        };
+
+        for index in indices {
+            self.load_source(index, ctx);
+        }
+    }
+
+    /// すべてのソースを取り直します（「リポジトリデータ更新」用）
+    ///
+    /// 取得中に呼ばれた場合は先の取得を打ち切り、最後の更新の結果だけを反映します。
+    pub(crate) fn refresh(&self, ctx: &egui::Context) {
+        let indices = {
+            let mut shared = self.shared.lock().unwrap();
+            if matches!(shared.state, PortfolioLoadState::NotStarted) {
+                drop(shared);
+                self.start_loading(ctx);
+                return;
+            }
+            shared.begin_refresh(false)
+        };
+        if indices.is_empty() {
+            self.shared.lock().unwrap().state = PortfolioLoadState::Loaded(Arc::default());
+        }
+        for index in indices {
+            self.load_source(index, ctx);
+        }
+    }
+
+    /// 前回の読み込みから `interval` が経過していれば、表示を保ったまま再取得します
+    ///
+    /// 毎フレーム呼び出します。ウィンドウが最小化されている間は呼ばないことで自動更新を止めます。
+    pub(crate) fn auto_refresh(&self, ctx: &egui::Context, interval: Duration) {
+        let indices = {
+            let mut shared = self.shared.lock().unwrap();
+            match shared.auto_refresh_wait(interval) {
+                None => return,
+                Some(Some(wait)) => {
+                    ctx.request_repaint_after(wait);
+                    return;
+                }
+                Some(None) => {
+                    log::info!("Refreshing portfolio automatically");
+                    shared.begin_refresh(true)
+                }
+            }
+        };
+        for index in indices {
+            self.load_source(index, ctx);
+        }
+    }
+
+    /// 失敗したソースを待たずに再取得します（「再試行」ボタン用）
+    pub(crate) fn retry_now(&self, ctx: &egui::Context) {
+        let indices = {
+            let mut shared = self.shared.lock().unwrap();
+            if !matches!(shared.state, PortfolioLoadState::Error { .. }) {
+                return;
+            }
+            shared.begin_attempt()
+        };
+        for index in indices {
+            self.load_source(index, ctx);
+        }
+    }
+
+    fn load_source(&self, index: usize, ctx: &egui::Context) {
+        match &self.sources[index] {
+            PortfolioSource::Url(url) => self.fetch_url(index, url, ctx),
+            #[cfg(not(target_arch = "wasm32"))]
+            PortfolioSource::Path(path) => {
+                self.shared
+                    .lock()
+                    .unwrap()
+                    .store_result(index, read_path(path));
+            }
+        }
+    }
+
+    /// ローカルファイルの変更を監視し、変更のたびに自動で再読み込みします
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watch_paths(&self, ctx: &egui::Context) {
+        for (index, source) in self.sources.iter().enumerate() {
+            if let PortfolioSource::Path(path) = source {
+                self.watch_path(index, path, ctx);
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watch_path(&self, index: usize, path: &std::path::Path, ctx: &egui::Context) {
+        let mut watcher = FileWatcher::new(path.to_owned());
+        let shared = Arc::downgrade(&self.shared);
+        let ctx = ctx.clone();
+        let watched_path = path.to_owned();
+        let spawned = std::thread::Builder::new()
+            .name("portfolio-watch".to_owned())
+            .spawn(move || loop {
+                std::thread::sleep(watch::POLL_INTERVAL);
+                // ローダーが破棄されたら監視を終了する
+                let Some(shared) = shared.upgrade() else {
+                    break;
+                };
+                if watcher.poll_changed() {
+                    log::info!("Reloading portfolio file {}", watched_path.display());
+                    shared
+                        .lock()
+                        .unwrap()
+                        .store_result(index, read_path(&watched_path));
+                    ctx.request_repaint();
+                }
+            });
+        if let Err(err) = spawned {
+            log::warn!("Failed to watch {}: {err}", path.display());
+        }
+    }
+
+    fn fetch_url(&self, index: usize, url: &str, ctx: &egui::Context) {
+        let shared = self.shared.clone();
+        let ctx = ctx.clone();
+        let source = url.to_owned();
+
+        let mut request = ehttp::Request::get(url);
+        let generation = {
+            let shared = self.shared.lock().unwrap();
+            if let Some(cached) = shared.cache.get(url) {
+                cached.apply_conditional_headers(&mut request);
+            }
+            shared.generation
+        };
+
+        self.fetcher.fetch(
+            request,
+            Box::new(move |result: ehttp::Result<ehttp::Response>| {
+                let mut shared = shared.lock().unwrap();
+                if shared.generation != generation {
+                    log::debug!("Discarding superseded response from {source}");
+                    return;
+                }
+                let result = shared.resolve_response(&source, result, cache::unix_now());
+                shared.store_result(index, result);
+                ctx.request_repaint();
+            }),
+        );
+    }
+}
+
+/// ローカルのポートフォリオファイルを読み込んで解釈します
+#[cfg(not(target_arch = "wasm32"))]
+fn read_path(path: &std::path::Path) -> SourceResult {
+    let source = PortfolioSource::Path(path.to_owned());
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            let format = PortfolioFormat::detect(None, &path.to_string_lossy(), &text);
+            parse_portfolio(&source, &text, format)
+        }
+        Err(e) => Err(PortfolioError::new(
+            source,
+            PortfolioErrorKind::Io(e.to_string()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::query::Query;
+    use super::search::SearchIndex;
+    use super::*;
+    use fetch::MemoryFetcher;
+
+    fn parsed(sections: Vec<RepoSection>) -> ParsedPortfolio {
+        ParsedPortfolio {
+            sections,
+            featured: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn load_featured_repo_reads_dedicated_yaml() {
+        let data = load_featured_repo();
+        assert!(
+            !data.name.is_empty(),
+            "featured data should load from its dedicated YAML"
+        );
+    }
+
+    /// 1 件だけの索引で検索し、`repo` が一致するかを返す
+    fn matches_query(repo: &RepoSummary, query: &str) -> bool {
+        let sections = [RepoSection {
+            name: "Section".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![repo.clone()],
+        }];
+        let query = Query::parse(query).expect("query should parse");
+        !SearchIndex::build(&sections, &TagRegistry::default())
+            .search(&sections, &query)
+            .is_empty()
+    }
+
+    #[test]
+    fn matches_query_by_name() {
+        let repo = RepoSummary {
+            name: "Test Repo".to_string(),
+            description: "A sample description".to_string(),
+            image_url: None,
+            repo_url: "https://example.com".to_string(),
+            badges: Vec::new(),
+            updated_at: None,
+        };
+        assert!(matches_query(&repo, "test"));
+        assert!(matches_query(&repo, "Repo"));
+        assert!(!matches_query(&repo, "missing"));
+    }
+
+    #[test]
+    fn matches_query_by_description() {
+        let repo = RepoSummary {
+            name: "Example".to_string(),
+            description: "A Rust project".to_string(),
+            image_url: None,
+            repo_url: "https://example.com".to_string(),
+            badges: Vec::new(),
+            updated_at: None,
+        };
+        assert!(matches_query(&repo, "rust"));
+        assert!(matches_query(&repo, "project"));
+    }
+
+    #[test]
+    fn matches_query_by_badges() {
+        let repo = RepoSummary {
+            name: "Example".to_string(),
+            description: "Description".to_string(),
+            image_url: None,
+            repo_url: "https://example.com".to_string(),
+            badges: Tag::split("Rust, WebAssembly, egui"),
+            updated_at: None,
+        };
+        assert!(matches_query(&repo, "webassembly"));
+        assert!(matches_query(&repo, "egui"));
+        assert!(!matches_query(&repo, "python"));
+    }
+
+    #[test]
+    fn matches_query_empty_returns_true() {
+        let repo = RepoSummary {
+            name: "Example".to_string(),
+            description: "Description".to_string(),
+            image_url: None,
+            repo_url: "https://example.com".to_string(),
+            badges: Vec::new(),
+            updated_at: None,
+        };
+        assert!(matches_query(&repo, ""));
+    }
+
+    fn sample_sections() -> Vec<RepoSection> {
+        vec![RepoSection {
+            name: "Tools".to_string(),
+            meta: SectionMeta {
+                row_key: Some("tools".to_string()),
+            },
+            items: vec![
+                RepoSummary {
+                    name: "Gridelle".to_string(),
+                    description: "表計算ライクなエディタ".to_string(),
+                    image_url: Some("https://example.com/gridelle.png".to_string()),
+                    repo_url: "https://example.com/gridelle".to_string(),
+                    badges: Tag::split("Rust, WASM"),
+                    updated_at: Some("2024-05-28".to_string()),
+                },
+                RepoSummary {
+                    name: "Minimal".to_string(),
+                    description: String::new(),
+                    image_url: None,
+                    repo_url: "https://example.com/minimal".to_string(),
+                    badges: Vec::new(),
+                    updated_at: None,
+                },
+            ],
+        }]
+    }
+
+    /// TOML の文書はテーブルである必要があるため `sections` に包んで書き出す
+    #[derive(serde::Serialize)]
+    struct SectionsDocument<'a> {
+        sections: &'a [RepoSection],
+    }
+
+    fn serialize_sections(format: PortfolioFormat, sections: &[RepoSection]) -> String {
+        match format {
+            PortfolioFormat::Yaml => serde_yaml::to_string(sections).unwrap(),
+            PortfolioFormat::Json => serde_json::to_string_pretty(sections).unwrap(),
+            PortfolioFormat::Toml => toml::to_string(&SectionsDocument { sections }).unwrap(),
+        }
+    }
+
+    const FORMATS: [PortfolioFormat; 3] = [
+        PortfolioFormat::Yaml,
+        PortfolioFormat::Json,
+        PortfolioFormat::Toml,
+    ];
+
+    #[test]
+    fn sections_round_trip_through_every_format() {
+        let source = PortfolioSource::Url("https://example.com/portfolio".to_string());
+        let sections = sample_sections();
+        for format in FORMATS {
+            let text = serialize_sections(format, &sections);
+            assert_eq!(PortfolioFormat::sniff(&text), format, "{text}");
+
+            let parsed = parse_portfolio(&source, &text, format)
+                .unwrap_or_else(|e| panic!("{format} should parse back: {e}\n{text}"));
+            assert!(
+                parsed.warnings.is_empty(),
+                "{format}: {:?}",
+                parsed.warnings
+            );
+            assert_eq!(parsed.sections, sections, "{format}");
+        }
+    }
+
+    #[test]
+    fn featured_repo_round_trips_through_every_format() {
+        let featured = load_featured_repo();
+        for format in FORMATS {
+            let text = match format {
+                PortfolioFormat::Yaml => serde_yaml::to_string(&featured).unwrap(),
+                PortfolioFormat::Json => serde_json::to_string(&featured).unwrap(),
+                PortfolioFormat::Toml => toml::to_string(&featured).unwrap(),
+            };
+            let parsed = format
+                .deserialize::<FeaturedRepo>(&text)
+                .unwrap_or_else(|e| panic!("{format} should parse back: {e}\n{text}"));
+            assert_eq!(parsed, featured, "{format}");
+        }
+    }
+
+    #[test]
+    fn loader_without_sources_loads_nothing() {
+        let loader = PortfolioLoader::new(Vec::new(), PortfolioCache::default());
+        loader.start_loading(&egui::Context::default());
+        assert!(matches!(
+            loader.state(),
+            PortfolioLoadState::Loaded(sections) if sections.is_empty()
+        ));
+    }
+
+    #[test]
+    fn store_result_merges_sources_in_declared_order() {
+        let section = |name: &str| RepoSection {
+            name: name.to_string(),
+            meta: SectionMeta::default(),
+            items: Vec::new(),
+        };
+        let mut shared = LoaderShared {
+            state: PortfolioLoadState::Loading { attempt: 1 },
+            results: vec![None, None],
+            ..Default::default()
+        };
+
+        // 2 番目のソースが先に完了しても宣言順で結合される
+        shared.store_result(1, Ok(parsed(vec![section("Second")])));
+        assert!(matches!(shared.state, PortfolioLoadState::Loading { .. }));
+        shared.store_result(0, Ok(parsed(vec![section("First A"), section("First B")])));
+
+        let PortfolioLoadState::Loaded(sections) = &shared.state else {
+            panic!("all sources finished, state should be loaded");
+        };
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["First A", "First B", "Second"]);
+    }
+
+    #[test]
+    fn store_result_collects_featured_and_bumps_revision_on_change() {
+        let featured = load_featured_repo();
+        let mut shared = LoaderShared {
+            results: vec![None, None],
+            ..Default::default()
+        };
+        let with_featured = ParsedPortfolio {
+            featured: vec![featured.clone()],
+            ..Default::default()
+        };
+        shared.store_result(0, Ok(with_featured.clone()));
+        shared.store_result(1, Ok(with_featured));
+        assert_eq!(shared.featured, [featured.clone(), featured.clone()]);
+        assert_eq!(shared.revision, 1);
+
+        // 注目リポジトリだけが変わった場合も内容の変更として扱う
+        shared.store_result(1, Ok(parsed(Vec::new())));
+        assert_eq!(shared.featured, [featured]);
+        assert_eq!(shared.revision, 2);
+        shared.store_result(1, Ok(parsed(Vec::new())));
+        assert_eq!(shared.revision, 2);
+    }
+
+    #[test]
+    fn store_result_reports_failed_source() {
+        let mut shared = LoaderShared {
+            state: PortfolioLoadState::Loading { attempt: 1 },
+            results: vec![None, None],
+            ..Default::default()
+        };
+        shared.store_result(0, Ok(parsed(Vec::new())));
+        shared.store_result(
+            1,
+            Err(PortfolioError::new(
+                PortfolioSource::Url("https://example.com".to_string()),
+                PortfolioErrorKind::Http {
+                    status: 404,
+                    status_text: "Not Found".to_string(),
+                },
+            )),
+        );
+        let PortfolioLoadState::Error {
+            error, retry_at, ..
+        } = &shared.state
+        else {
+            panic!("failed source should turn the state into an error");
+        };
+        assert!(matches!(
+            error.kind,
+            PortfolioErrorKind::Http { status: 404, .. }
+        ));
+        assert_eq!(*retry_at, None, "404 is not transient and is not retried");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn loader_reads_local_path_sources() {
+        let path =
+            std::env::temp_dir().join(format!("map_panels_portfolio_{}.yaml", std::process::id()));
+        std::fs::write(
+            &path,
+            "- name: Local\n  items:\n    - name: Repo\n      description: Desc\n      image_url: null\n      repo_url: https://example.com\n",
+        )
+        .expect("temp file should be writable");
+
+        let loader = PortfolioLoader::new(
+            vec![PortfolioSource::Path(path.clone())],
+            PortfolioCache::default(),
+        );
+        loader.start_loading(&egui::Context::default());
+        std::fs::remove_file(&path).ok();
+
+        let PortfolioLoadState::Loaded(sections) = loader.state() else {
+            panic!("local source should load synchronously");
+        };
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].items[0].name, "Repo");
+    }
+
+    #[test]
+    fn store_result_replaces_reloaded_source() {
+        let section = |name: &str| RepoSection {
+            name: name.to_string(),
+            meta: SectionMeta::default(),
+            items: Vec::new(),
+        };
+        let mut shared = LoaderShared {
+            state: PortfolioLoadState::Loading { attempt: 1 },
+            results: vec![None, None],
+            ..Default::default()
+        };
+        shared.store_result(0, Ok(parsed(vec![section("Remote")])));
+        shared.store_result(1, Ok(parsed(vec![section("Local v1")])));
+        shared.store_result(1, Ok(parsed(vec![section("Local v2")])));
+
+        let PortfolioLoadState::Loaded(sections) = &shared.state else {
+            panic!("reloaded source should keep the state loaded");
+        };
+        let names: Vec<&str> = sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Remote", "Local v2"]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn loader_reloads_local_file_on_change() {
+        let path = std::env::temp_dir().join(format!(
+            "map_panels_live_reload_{}.yaml",
+            std::process::id()
+        ));
+        std::fs::write(&path, "- name: Before\n  items: []\n")
+            .expect("temp file should be writable");
+
+        let loader = PortfolioLoader::new(
+            vec![PortfolioSource::Path(path.clone())],
+            PortfolioCache::default(),
+        );
+        loader.start_loading(&egui::Context::default());
+        std::fs::write(&path, "- name: After edit\n  items: []\n").expect("rewrite should work");
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let reloaded = loop {
+            if let PortfolioLoadState::Loaded(sections) = loader.state() {
+                if sections[0].name == "After edit" {
+                    break true;
+                }
+            }
+            if std::time::Instant::now() > deadline {
+                break false;
+            }
+            std::thread::sleep(watch::POLL_INTERVAL / 2);
+        };
+        std::fs::remove_file(&path).ok();
+        assert!(reloaded, "edited file should be picked up by the watcher");
+    }
+
+    const CACHE_URL: &str = "https://example.com/portfolio.yaml";
+    const CACHE_BODY: &str = "- name: Cached\n  items: []\n";
+
+    fn response(status: u16, body: &str, headers: &[(&str, &str)]) -> ehttp::Response {
+        ehttp::Response {
+            url: CACHE_URL.to_string(),
+            ok: (200..300).contains(&status),
+            status,
+            status_text: String::new(),
+            headers: ehttp::Headers::new(headers),
+            bytes: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn shared_with_cache(fetched_at: u64) -> LoaderShared {
+        let mut cache = PortfolioCache::default();
+        cache.insert(
+            CACHE_URL,
+            CachedResponse {
+                body: CACHE_BODY.to_string(),
+                etag: Some("\"v1\"".to_string()),
+                last_modified: None,
+                content_type: None,
+                fetched_at,
+            },
+        );
+        LoaderShared {
+            cache,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_response_caches_successful_body() {
+        let mut shared = LoaderShared::default();
+        let sections = shared
+            .resolve_response(
+                CACHE_URL,
+                Ok(response(200, CACHE_BODY, &[("ETag", "\"v2\"")])),
+                100,
+            )
+            .expect("valid body should parse")
+            .sections;
+        assert_eq!(sections[0].name, "Cached");
+
+        let cached = shared.cache.get(CACHE_URL).expect("body should be cached");
+        assert_eq!(cached.etag.as_deref(), Some("\"v2\""));
+        assert_eq!(shared.cache.age(160), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn resolve_response_treats_not_modified_as_fresh() {
+        let mut shared = shared_with_cache(10);
+        let sections = shared
+            .resolve_response(CACHE_URL, Ok(response(304, "", &[])), 500)
+            .expect("304 should reuse the cached body")
+            .sections;
+        assert_eq!(sections[0].name, "Cached");
+        assert_eq!(shared.cache.age(500), Some(Duration::ZERO));
+        assert!(shared.fallback_errors.is_empty());
+    }
+
+    #[test]
+    fn resolve_response_falls_back_to_cache_when_offline() {
+        let mut shared = shared_with_cache(10);
+        let sections = shared
+            .resolve_response(CACHE_URL, Err("connection refused".to_string()), 500)
+            .expect("cached body should be used offline")
+            .sections;
+        assert_eq!(sections[0].name, "Cached");
+        assert_eq!(shared.fallback_errors.len(), 1);
+        // 代替表示ではキャッシュの取得時刻を更新しない
+        assert_eq!(shared.cache.age(500), Some(Duration::from_secs(490)));
+    }
+
+    #[test]
+    fn resolve_response_keeps_cache_on_invalid_body() {
+        let mut shared = shared_with_cache(10);
+        let result = shared.resolve_response(CACHE_URL, Ok(response(200, "{{{", &[])), 500);
+        assert!(
+            matches!(
+                result,
+                Err(PortfolioError {
+                    kind: PortfolioErrorKind::Parse(_),
+                    ..
+                })
+            ),
+            "parse errors should not be hidden by the cache"
+        );
+        assert_eq!(
+            shared.cache.get(CACHE_URL).map(|c| c.body.as_str()),
+            Some(CACHE_BODY)
+        );
+    }
+
+    #[test]
+    fn resolve_response_without_cache_reports_error() {
+        let mut shared = LoaderShared::default();
+        let error = shared
+            .resolve_response(CACHE_URL, Ok(response(500, "", &[])), 0)
+            .expect_err("500 without cache should fail");
+        assert_eq!(error.source, PortfolioSource::Url(CACHE_URL.to_string()));
+        assert!(matches!(
+            error.kind,
+            PortfolioErrorKind::Http { status: 500, .. }
+        ));
+    }
+
+    #[test]
+    fn resolve_response_reports_invalid_utf8() {
+        let mut shared = LoaderShared::default();
+        let mut invalid = response(200, "", &[]);
+        invalid.bytes = vec![0xff, 0xfe];
+        let error = shared
+            .resolve_response(CACHE_URL, Ok(invalid), 0)
+            .expect_err("invalid UTF-8 should fail");
+        assert_eq!(error.kind, PortfolioErrorKind::InvalidUtf8);
+    }
+
+    #[test]
+    fn not_modified_without_cache_is_an_http_error() {
+        let mut shared = LoaderShared::default();
+        let error = shared
+            .resolve_response(CACHE_URL, Ok(response(304, "", &[])), 0)
+            .expect_err("304 without cache cannot be served");
+        assert!(matches!(
+            error.kind,
+            PortfolioErrorKind::Http { status: 304, .. }
+        ));
+    }
+
+    /// テストから `advance` で進める時計。再試行の待ち時間を実時間で待たずに確かめる
+    #[derive(Default)]
+    struct FakeClock {
+        now: Mutex<Duration>,
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Duration {
+            *self.now.lock().unwrap()
+        }
+    }
+
+    impl FakeClock {
+        fn advance(&self, by: Duration) {
+            *self.now.lock().unwrap() += by;
+        }
+    }
+
+    fn fake_loader() -> (
+        PortfolioLoader<MemoryFetcher>,
+        Arc<MemoryFetcher>,
+        Arc<FakeClock>,
+    ) {
+        let fetcher = Arc::new(MemoryFetcher::default());
+        let clock = Arc::new(FakeClock::default());
+        let loader = PortfolioLoader::with_backends(
+            vec![PortfolioSource::Url(CACHE_URL.to_string())],
+            PortfolioCache::default(),
+            fetcher.clone(),
+            clock.clone(),
+        );
+        (loader, fetcher, clock)
+    }
+
+    #[test]
+    fn successful_fetch_moves_from_loading_to_loaded() {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, _) = fake_loader();
+        fetcher.respond(CACHE_URL, 200, CACHE_BODY);
+        assert!(matches!(loader.state(), PortfolioLoadState::NotStarted));
+
+        loader.start_loading(&ctx);
+        assert!(matches!(
+            loader.state(),
+            PortfolioLoadState::Loading { attempt: 1 }
+        ));
+        // 読み込み中に再度呼ばれても二重に取得しない
+        loader.start_loading(&ctx);
+        assert_eq!(fetcher.pending_urls(), [CACHE_URL]);
+
+        fetcher.complete_all();
+        let PortfolioLoadState::Loaded(sections) = loader.state() else {
+            panic!("successful fetch should load the sections");
+        };
+        assert_eq!(sections[0].name, "Cached");
+        assert!(loader.cache().get(CACHE_URL).is_some());
+    }
+
+    /// 1 回だけ取得させ、失敗したときのエラーを返す
+    fn load_error(status: u16, body: &[u8]) -> PortfolioError {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, _) = fake_loader();
+        fetcher.respond(CACHE_URL, status, body);
+        loader.start_loading(&ctx);
+        fetcher.complete_all();
+        match loader.state() {
+            PortfolioLoadState::Error { error, .. } => error,
+            _ => panic!("status {status} with {body:?} should fail"),
+        }
+    }
+
+    #[test]
+    fn http_error_is_reported_without_retry() {
+        let error = load_error(404, b"");
+        assert_eq!(
+            error.kind,
+            PortfolioErrorKind::Http {
+                status: 404,
+                status_text: "Not Found".to_string(),
+            }
+        );
+        assert_eq!(error.source, PortfolioSource::Url(CACHE_URL.to_string()));
+    }
+
+    #[test]
+    fn invalid_utf8_body_is_reported() {
+        let error = load_error(200, &[0x2d, 0x20, 0xff, 0xfe]);
+        assert_eq!(error.kind, PortfolioErrorKind::InvalidUtf8);
+    }
+
+    #[test]
+    fn malformed_yaml_is_reported_with_location() {
+        let error = load_error(200, b"- name: Broken\n  items: [\n");
+        let PortfolioErrorKind::Parse(diagnostic) = error.kind else {
+            panic!("malformed YAML should be a parse error, got {}", error.kind);
+        };
+        assert_eq!(diagnostic.format, PortfolioFormat::Yaml);
+        assert!(diagnostic.location.is_some());
+    }
+
+    #[test]
+    fn failed_fetch_schedules_retry_with_backoff() {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, clock) = fake_loader();
+        fetcher.respond_with(CACHE_URL, Err("offline".to_string()));
+        loader.start_loading(&ctx);
+        assert!(matches!(
+            loader.state(),
+            PortfolioLoadState::Loading { attempt: 1 }
+        ));
+
+        fetcher.complete_all();
+        let PortfolioLoadState::Error {
+            attempt, retry_at, ..
+        } = loader.state()
+        else {
+            panic!("failed fetch should report an error");
+        };
+        assert_eq!(attempt, 1);
+        let retry_at = retry_at.expect("first failure should be retried");
+        assert!((Duration::from_millis(500)..=Duration::from_secs(1)).contains(&retry_at));
+
+        // 再試行時刻までは再取得しない
+        loader.start_loading(&ctx);
+        assert!(fetcher.pending_urls().is_empty());
+
+        clock.advance(retry_at);
+        loader.start_loading(&ctx);
+        assert_eq!(fetcher.pending_urls().len(), 1);
+        assert!(matches!(
+            loader.state(),
+            PortfolioLoadState::Loading { attempt: 2 }
+        ));
+
+        fetcher.respond(CACHE_URL, 200, CACHE_BODY);
+        fetcher.complete_all();
+        assert!(matches!(loader.state(), PortfolioLoadState::Loaded(_)));
+    }
+
+    #[test]
+    fn retries_stop_after_max_attempts() {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, clock) = fake_loader();
+        fetcher.respond(CACHE_URL, 503, "");
+        loader.start_loading(&ctx);
+
+        let max_attempts = RetryPolicy::default().max_attempts;
+        for _ in 1..max_attempts {
+            fetcher.complete_all();
+            clock.advance(RetryPolicy::default().max_delay);
+            loader.start_loading(&ctx);
+        }
+        fetcher.complete_all();
+
+        let PortfolioLoadState::Error {
+            attempt, retry_at, ..
+        } = loader.state()
+        else {
+            panic!("every attempt failed, state should be an error");
+        };
+        assert_eq!(attempt, max_attempts);
+        assert_eq!(retry_at, None, "automatic retries should give up");
+        assert_eq!(loader.time_until_retry(), None);
+
+        // 手動の再試行はいつでも可能
+        loader.retry_now(&ctx);
+        assert_eq!(fetcher.pending_urls().len(), 1);
+        fetcher.respond(CACHE_URL, 200, CACHE_BODY);
+        fetcher.complete_all();
+        assert!(matches!(loader.state(), PortfolioLoadState::Loaded(_)));
+    }
+
+    fn section_names(state: PortfolioLoadState) -> Vec<String> {
+        match state {
+            PortfolioLoadState::Loaded(sections) => sections
+                .iter()
+                .map(|section| section.name.clone())
+                .collect(),
+            _ => panic!("portfolio should be loaded"),
+        }
+    }
+
+    #[test]
+    fn refresh_keeps_only_the_newest_response() {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, _) = fake_loader();
+        fetcher.respond(CACHE_URL, 200, CACHE_BODY);
+        loader.start_loading(&ctx);
+        fetcher.complete_all();
+
+        loader.refresh(&ctx);
+        assert!(matches!(loader.state(), PortfolioLoadState::Refreshing));
+        loader.refresh(&ctx);
+        assert_eq!(fetcher.pending_urls().len(), 2);
+
+        // 先に始めた更新の応答は後から届いても捨てる
+        fetcher.respond(CACHE_URL, 200, "- name: Superseded\n  items: []\n");
+        fetcher.complete_next();
+        assert!(matches!(loader.state(), PortfolioLoadState::Refreshing));
+
+        fetcher.respond(CACHE_URL, 200, "- name: Newest\n  items: []\n");
+        fetcher.complete_next();
+        assert_eq!(section_names(loader.state()), ["Newest"]);
+    }
+
+    #[test]
+    fn refresh_ignores_responses_completing_out_of_order() {
+        let ctx = egui::Context::default();
+        let (loader, fetcher, _) = fake_loader();
+        fetcher.respond(CACHE_URL, 200, "- name: Initial\n  items: []\n");
+        loader.start_loading(&ctx);
+        loader.refresh(&ctx);
+
+        // 新しい更新が先に完了し、その後に初回の応答が届く
+        let (_, stale) = fetcher.take_next().expect("initial request is pending");
+        fetcher.respond(CACHE_URL, 200, "- name: Refreshed\n  items: []\n");
+        fetcher.complete_all();
+        assert_eq!(section_names(loader.state()), ["Refreshed"]);
+
+        stale(Ok(response(200, "- name: Stale\n  items: []\n", &[])));
+        assert_eq!(section_names(loader.state()), ["Refreshed"]);
+    }
+
+    #[test]
+    fn auto_refresh_reloads_in_background_after_interval() {
+        let ctx = egui::Context::default();
+        let interval = Duration::from_secs(300);
+        let (loader, fetcher, clock) = fake_loader();
+        fetcher.respond(CACHE_URL, 200, CACHE_BODY);
+        loader.start_loading(&ctx);
+        loader.auto_refresh(&ctx, interval);
+        fetcher.complete_all();
+        assert_eq!(loader.revision(), 1);
+
+        clock.advance(interval - Duration::from_secs(1));
+        loader.auto_refresh(&ctx, interval);
+        assert!(fetcher.pending_urls().is_empty(), "interval has not passed");
+
+        clock.advance(Duration::from_secs(1));
+        loader.auto_refresh(&ctx, interval);
+        loader.auto_refresh(&ctx, interval);
+        assert_eq!(fetcher.pending_urls(), [CACHE_URL]);
+        // 取得中も前回のデータを表示し続ける
+        assert_eq!(section_names(loader.state()), ["Cached"]);
+
+        // 内容が同じなら版数は変わらない
+        fetcher.complete_all();
+        assert_eq!(loader.revision(), 1);
+
+        clock.advance(interval);
+        loader.auto_refresh(&ctx, interval);
+        fetcher.respond(CACHE_URL, 200, "- name: Updated\n  items: []\n");
+        fetcher.complete_all();
+        assert_eq!(loader.revision(), 2);
+        assert_eq!(section_names(loader.state()), ["Updated"]);
+    }
+
+    #[test]
+    fn retry_refetches_only_failed_sources() {
+        let ctx = egui::Context::default();
+        let fetcher = Arc::new(MemoryFetcher::default());
+        let loader = PortfolioLoader::with_backends(
+            vec![
+                PortfolioSource::Url("https://example.com/a.yaml".to_string()),
+                PortfolioSource::Url("https://example.com/b.yaml".to_string()),
+            ],
+            PortfolioCache::default(),
+            fetcher.clone(),
+            Arc::new(FakeClock::default()),
+        );
+        fetcher.respond("https://example.com/a.yaml", 200, CACHE_BODY);
+        fetcher.respond_with("https://example.com/b.yaml", Err("offline".to_string()));
+        loader.start_loading(&ctx);
+        fetcher.complete_all();
+
+        loader.retry_now(&ctx);
+        assert_eq!(fetcher.pending_urls(), ["https://example.com/b.yaml"]);
+    }
+}
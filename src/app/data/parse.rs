@@ -0,0 +1,552 @@
+//! Lenient parsing of portfolio documents.
+//!
+//! A single malformed item should not hide the rest of the portfolio, so the
+//! document is first read as a generic value and every section and item is
+//! then deserialized on its own. Invalid entries are skipped and reported
+//! as [`ParseWarning`]s; only documents whose overall shape is wrong fail.
+//!
+//! The generic value no longer knows where anything was in the text, so a
+//! skipped entry is read once more straight from the text with the format's
+//! own deserializer to find the line it failed on.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_yaml::Value;
+
+use super::{
+    ExcerptLine, FeaturedRepo, ParseDiagnostic, PortfolioError, PortfolioErrorKind,
+    PortfolioFormat, RepoSection, RepoSummary, TextLocation,
+};
+use crate::app::config::PortfolioSource;
+
+/// A section or item that was skipped because it could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ParseWarning {
+    pub(crate) source: PortfolioSource,
+    /// Path to the skipped value, e.g. `[1].items[3]`.
+    pub(crate) path: String,
+    /// Field of the entry the error is about, e.g. `repo_url`, when known.
+    pub(crate) field: Option<String>,
+    /// `name` of the skipped entry when it has one, to make it recognizable.
+    pub(crate) name: Option<String>,
+    pub(crate) message: String,
+    pub(crate) location: Option<TextLocation>,
+    /// Lines around `location`, including the offending one.
+    pub(crate) excerpt: Vec<ExcerptLine>,
+}
+
+impl ParseWarning {
+    /// Path including the field, e.g. `[1].items[3].repo_url`.
+    pub(crate) fn field_path(&self) -> String {
+        match &self.field {
+            Some(field) => format!("{}.{field}", self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.field_path())?;
+        if let Some(name) = &self.name {
+            write!(f, " ({name})")?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(location) = self.location {
+            write!(f, " at line {} column {}", location.line, location.column)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sections parsed from one source, along with the entries that were skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParsedPortfolio {
+    pub(crate) sections: Vec<RepoSection>,
+    /// Repositories for the hero section, empty when the source has none.
+    pub(crate) featured: Vec<FeaturedRepo>,
+    pub(crate) warnings: Vec<ParseWarning>,
+}
+
+/// Parses a portfolio document, skipping invalid sections and items.
+///
+/// The document is either a list of sections or a table with a `sections`
+/// list and/or a `featured` entry (a single repository or a list); TOML only
+/// allows the latter. Syntax errors and documents of any other shape still fail.
+pub(crate) fn parse_portfolio(
+    source: &PortfolioSource,
+    text: &str,
+    format: PortfolioFormat,
+) -> Result<ParsedPortfolio, PortfolioError> {
+    let error = |kind| PortfolioError::new(source.clone(), kind);
+    let value = match format {
+        PortfolioFormat::Toml => from_toml(format.deserialize(text).map_err(error)?),
+        _ => format.deserialize(text).map_err(error)?,
+    };
+    // 各エントリまでの道のり。スキップした理由を本文から探し直すのに使う
+    let mut sections_steps = Vec::new();
+    let (raw_sections, raw_featured) = match value {
+        Value::Sequence(raw_sections) => (raw_sections, None),
+        Value::Mapping(mut document) => {
+            sections_steps.push(Step::Key("sections"));
+            let raw_featured = document.remove("featured");
+            match document.remove("sections") {
+                Some(Value::Sequence(raw_sections)) => (raw_sections, raw_featured),
+                None if raw_featured.is_some() => (Vec::new(), raw_featured),
+                _ => {
+                    return Err(error(PortfolioErrorKind::invalid_shape(
+                        format,
+                        "expected a `sections` list",
+                    )))
+                }
+            }
+        }
+        _ => {
+            return Err(error(PortfolioErrorKind::invalid_shape(
+                format,
+                "expected a list of sections",
+            )))
+        }
+    };
+
+    let mut parsed = ParsedPortfolio::default();
+    let mut warnings = Vec::new();
+    let mut warn = |path: String,
+                    name: Option<String>,
+                    message: String,
+                    located: Option<Box<ParseDiagnostic>>| {
+        // 読み直しても同じ理由で失敗したときだけ、その位置を信用する
+        let located = located.filter(|diagnostic| diagnostic.message == message);
+        let field = field_of(&message, located.as_ref().and_then(|d| d.path.as_deref()));
+        let (location, excerpt) = located
+            .map(|diagnostic| (diagnostic.location, diagnostic.excerpt))
+            .unwrap_or_default();
+        warnings.push(ParseWarning {
+            source: source.clone(),
+            path,
+            field,
+            name,
+            message,
+            location,
+            excerpt,
+        });
+    };
+
+    for (index, raw_section) in raw_sections.into_iter().enumerate() {
+        let path = format!("[{index}]");
+        let name = entry_name(&raw_section);
+        let steps = [sections_steps.as_slice(), &[Step::Index(index)]].concat();
+        let locate_section = || locate::<RepoSection>(format, text, &steps);
+
+        // items は 1 件ずつ解釈するため、セクション本体からは一旦取り除く
+        let mut raw_section = raw_section;
+        let raw_items = match raw_section
+            .as_mapping_mut()
+            .and_then(|mapping| mapping.remove("items"))
+        {
+            Some(Value::Sequence(items)) => items,
+            Some(_) => {
+                warn(path, name, "`items` is not a list".to_owned(), None);
+                continue;
+            }
+            None => {
+                let message = "missing field `items`".to_owned();
+                warn(path, name, message, locate_section());
+                continue;
+            }
+        };
+        if let Some(mapping) = raw_section.as_mapping_mut() {
+            mapping.insert(Value::from("items"), Value::Sequence(Vec::new()));
+        }
+        let mut section = match serde_yaml::from_value::<RepoSection>(raw_section) {
+            Ok(section) => section,
+            Err(e) => {
+                warn(path, name, e.to_string(), locate_section());
+                continue;
+            }
+        };
+
+        for (item_index, raw_item) in raw_items.into_iter().enumerate() {
+            let item_name = entry_name(&raw_item);
+            match serde_yaml::from_value::<RepoSummary>(raw_item) {
+                Ok(item) => section.items.push(item),
+                Err(e) => {
+                    let item_steps = [
+                        steps.as_slice(),
+                        &[Step::Key("items"), Step::Index(item_index)],
+                    ]
+                    .concat();
+                    warn(
+                        format!("{path}.items[{item_index}]"),
+                        item_name,
+                        e.to_string(),
+                        locate::<RepoSummary>(format, text, &item_steps),
+                    );
+                }
+            }
+        }
+        parsed.sections.push(section);
+    }
+
+    let raw_featured = match raw_featured {
+        None => Vec::new(),
+        Some(Value::Sequence(list)) => list
+            .into_iter()
+            .enumerate()
+            .map(|(index, raw)| {
+                let steps = vec![Step::Key("featured"), Step::Index(index)];
+                (format!("featured[{index}]"), steps, raw)
+            })
+            .collect(),
+        Some(single) => vec![("featured".to_owned(), vec![Step::Key("featured")], single)],
+    };
+    for (path, steps, raw) in raw_featured {
+        let name = entry_name(&raw);
+        match serde_yaml::from_value::<FeaturedRepo>(raw) {
+            Ok(featured) => parsed.featured.push(featured),
+            Err(e) => warn(
+                path,
+                name,
+                e.to_string(),
+                locate::<FeaturedRepo>(format, text, &steps),
+            ),
+        }
+    }
+
+    parsed.warnings = warnings;
+    for warning in &parsed.warnings {
+        log::warn!("Skipped portfolio entry {warning}");
+    }
+    Ok(parsed)
+}
+
+/// Converts a TOML document to the generic value the entries are read from.
+/// Date-times become their text, so bare dates like `updated_at = 2024-05-28`
+/// read as strings.
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::from(number),
+        toml::Value::Float(number) => Value::from(number),
+        toml::Value::Boolean(flag) => Value::Bool(flag),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(key, value)| (Value::String(key), from_toml(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn entry_name(value: &Value) -> Option<String> {
+    value.get("name").and_then(Value::as_str).map(str::to_owned)
+}
+
+/// Field an entry error is about: the last key of serde_yaml's path, or the
+/// field named by a "missing field" message.
+fn field_of(message: &str, path: Option<&str>) -> Option<String> {
+    path.and_then(|path| path.rsplit('.').next())
+        .filter(|field| !field.is_empty() && !field.ends_with(']'))
+        .or_else(|| {
+            message
+                .strip_prefix("missing field `")
+                .and_then(|rest| rest.strip_suffix('`'))
+        })
+        .map(str::to_owned)
+}
+
+/// One step from the document root towards an entry.
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Key(&'static str),
+    Index(usize),
+}
+
+/// Reads the entry at `steps` as `T` straight from `text`, ignoring the rest
+/// of the document, and returns the diagnostic it fails with.
+fn locate<T: DeserializeOwned>(
+    format: PortfolioFormat,
+    text: &str,
+    steps: &[Step],
+) -> Option<Box<ParseDiagnostic>> {
+    let seed = Entry::<T> {
+        steps,
+        entry: PhantomData,
+    };
+    let result = match format {
+        PortfolioFormat::Yaml => seed
+            .deserialize(serde_yaml::Deserializer::from_str(text))
+            .map_err(|e| PortfolioErrorKind::from_yaml(&e, text)),
+        PortfolioFormat::Json => seed
+            .deserialize(&mut serde_json::Deserializer::from_str(text))
+            .map_err(|e| PortfolioErrorKind::from_json(&e, text)),
+        PortfolioFormat::Toml => toml::Deserializer::parse(text)
+            .and_then(|deserializer| seed.deserialize(deserializer))
+            .map_err(|e| PortfolioErrorKind::from_toml(&e, text)),
+    };
+    match result {
+        Err(PortfolioErrorKind::Parse(diagnostic)) => Some(diagnostic),
+        _ => None,
+    }
+}
+
+/// Walks `steps` into a document and deserializes the value there as `T`.
+struct Entry<'a, T> {
+    steps: &'a [Step],
+    entry: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for Entry<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self.steps.split_first() {
+            None => T::deserialize(deserializer).map(drop),
+            Some((&step, steps)) => deserializer.deserialize_any(Walk {
+                step,
+                rest: Entry::<T> {
+                    steps,
+                    entry: PhantomData,
+                },
+            }),
+        }
+    }
+}
+
+/// Takes one step into a list or table, skipping over everything else in it.
+struct Walk<'a, T> {
+    step: Step,
+    rest: Entry<'a, T>,
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for Walk<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a list or a table")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        if let Step::Index(index) = self.step {
+            for _ in 0..index {
+                if seq.next_element::<IgnoredAny>()?.is_none() {
+                    return Ok(());
+                }
+            }
+            seq.next_element_seed(self.rest)?;
+        }
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut rest = Some(self.rest);
+        while let Some(key) = map.next_key::<String>()? {
+            let wanted = matches!(self.step, Step::Key(wanted) if wanted == key);
+            match rest.take_if(|_| wanted) {
+                Some(rest) => map.next_value_seed(rest)?,
+                None => map.next_value::<IgnoredAny>().map(drop)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> PortfolioSource {
+        PortfolioSource::Url("https://example.com/portfolio.yaml".to_string())
+    }
+
+    #[test]
+    fn invalid_items_are_skipped_with_warnings() {
+        let text = "\
+- name: Tools
+  items:
+    - name: Good
+      description: Fine
+      repo_url: https://example.com/good
+    - name: Broken
+      description: No URL
+    - name: Also good
+      description: Fine
+      repo_url: https://example.com/also-good
+";
+        let parsed = parse_portfolio(&source(), text, PortfolioFormat::Yaml)
+            .expect("document shape is valid");
+
+        let names: Vec<&str> = parsed.sections[0]
+            .items
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(names, ["Good", "Also good"]);
+
+        let [warning] = parsed.warnings.as_slice() else {
+            panic!("exactly one item should be skipped: {:?}", parsed.warnings);
+        };
+        assert_eq!(warning.path, "[0].items[1]");
+        assert_eq!(warning.name.as_deref(), Some("Broken"));
+        assert!(warning.message.contains("repo_url"), "{warning}");
+    }
+
+    #[test]
+    fn skipped_items_point_at_the_offending_line() {
+        let yaml = "\
+sections:
+  - name: Tools
+    items:
+      - name: Good
+        description: Fine
+        repo_url: https://example.com/good
+      - name: Typo
+        description: Misspelled key
+        repo_ur: https://example.com/typo
+";
+        let json = r#"[
+  {"name": "Tools", "items": [
+    {"name": "Good", "description": "Fine", "repo_url": "https://example.com/good"},
+    {"name": "Typo", "description": "Misspelled key", "repo_ur": "https://example.com/typo"}
+  ]}
+]"#;
+        let toml = r#"
+[[sections]]
+name = "Tools"
+
+[[sections.items]]
+name = "Good"
+description = "Fine"
+repo_url = "https://example.com/good"
+
+[[sections.items]]
+name = "Typo"
+description = "Misspelled key"
+repo_ur = "https://example.com/typo"
+"#;
+        for (text, format) in [
+            (yaml, PortfolioFormat::Yaml),
+            (json, PortfolioFormat::Json),
+            (toml, PortfolioFormat::Toml),
+        ] {
+            let parsed = parse_portfolio(&source(), text, format).expect("document shape is valid");
+            let [warning] = parsed.warnings.as_slice() else {
+                panic!(
+                    "{format}: one item should be skipped: {:?}",
+                    parsed.warnings
+                );
+            };
+            assert_eq!(warning.field_path(), "[0].items[1].repo_url", "{format}");
+            let location = warning
+                .location
+                .unwrap_or_else(|| panic!("{format}: the item is located: {warning}"));
+            let around: Vec<&str> = warning
+                .excerpt
+                .iter()
+                .map(|line| line.text.as_str())
+                .collect();
+            assert!(
+                around.iter().any(|line| line.contains("repo_ur")),
+                "{format}: excerpt should show the misspelled key around line {}: {around:?}",
+                location.line
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_sections_are_skipped_but_others_kept() {
+        let text = "\
+- name: Without items
+- items: []
+- name: Kept
+  items: []
+- name: Bad items
+  items: nope
+";
+        let parsed = parse_portfolio(&source(), text, PortfolioFormat::Yaml)
+            .expect("document shape is valid");
+
+        let names: Vec<&str> = parsed.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Kept"]);
+        let paths: Vec<&str> = parsed.warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["[0]", "[1]", "[3]"]);
+    }
+
+    #[test]
+    fn wrong_document_shape_still_fails() {
+        for text in ["name: not a list", "- [unterminated"] {
+            let err = parse_portfolio(&source(), text, PortfolioFormat::Yaml)
+                .expect_err("shape errors are fatal");
+            assert!(
+                matches!(err.kind, PortfolioErrorKind::Parse(_)),
+                "{text:?} should fail with a YAML error, got {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn hand_written_toml_reads_bare_dates() {
+        let text = r#"
+[[sections]]
+name = "Tools"
+
+[[sections.items]]
+name = "Gridelle"
+description = "Spreadsheet-like editor"
+repo_url = "https://example.com/gridelle"
+updated_at = 2024-05-28
+
+[[sections.items]]
+name = "Stamped"
+description = "With a time"
+repo_url = "https://example.com/stamped"
+updated_at = 2024-05-28T09:30:00Z
+"#;
+        let parsed = parse_portfolio(&source(), text, PortfolioFormat::Toml)
+            .expect("TOML with a sections table is valid");
+        assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+        let items = &parsed.sections[0].items;
+        assert_eq!(items[0].name, "Gridelle");
+        assert_eq!(items[0].updated_at.as_deref(), Some("2024-05-28"));
+        assert_eq!(items[1].updated_at.as_deref(), Some("2024-05-28T09:30:00Z"));
+    }
+
+    #[test]
+    fn featured_accepts_a_single_entry_or_a_list() {
+        let single = "\
+featured:
+  name: Hero
+  description: Shown on top
+  repository: https://example.com/hero
+  tags: [Rust]
+  updated_at: \"2024-05-28\"
+";
+        let parsed = parse_portfolio(&source(), single, PortfolioFormat::Yaml)
+            .expect("a document with only `featured` is valid");
+        assert!(parsed.sections.is_empty());
+        assert_eq!(parsed.featured.len(), 1);
+        assert_eq!(parsed.featured[0].name, "Hero");
+
+        let list = r#"{
+  "sections": [],
+  "featured": [
+    {"name": "One", "description": "", "repository": "https://example.com/1", "tags": [], "updated_at": "2024-01-01"},
+    {"name": "Broken"},
+    {"name": "Two", "description": "", "repository": "https://example.com/2", "tags": [], "updated_at": "2024-01-02"}
+  ]
+}"#;
+        let parsed = parse_portfolio(&source(), list, PortfolioFormat::Json)
+            .expect("document shape is valid");
+        let names: Vec<&str> = parsed.featured.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["One", "Two"]);
+        let [warning] = parsed.warnings.as_slice() else {
+            panic!("the broken entry should be skipped: {:?}", parsed.warnings);
+        };
+        assert_eq!(warning.path, "featured[1]");
+    }
+}
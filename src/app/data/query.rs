@@ -0,0 +1,530 @@
+//! Search box query language.
+//!
+//! A query is a list of whitespace-separated clauses that must all hold:
+//!
+//! - `word` matches name, description or badges, tolerating typos
+//! - `"a phrase"` matches the exact text, ignoring case
+//! - `tag:rust` matches a badge exactly, ignoring case
+//! - `name:foo` matches text in the name only
+//! - `section:"Tools"` keeps only the named section
+//! - `updated:>2024-01-01` compares the last update with a date; the operator
+//!   is one of `>`, `>=`, `<`, `<=` or none, and the date may be
+//!   `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+//! - a leading `-` excludes items matching the clause, e.g. `-python`; words
+//!   and phrases are excluded on exact matches only
+//!
+//! Field values can be quoted to include spaces. An unknown `field:` prefix is
+//! an error only when it looks like a typo of a field: four letters or more,
+//! one edit off, and not a common word like `game`. Otherwise, as in `tags:x`
+//! or a pasted URL, the whole word is searched as text.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::fuzzy::edit_distance;
+
+/// Parsed search query: every clause must hold for an item to match.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Query {
+    pub(crate) clauses: Vec<Clause>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Clause {
+    /// Set by a leading `-`: the clause must *not* hold.
+    pub(crate) negated: bool,
+    pub(crate) filter: Filter,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Filter {
+    /// A bare word, matched fuzzily against name, description and badges.
+    Word(String),
+    /// A quoted phrase, matched exactly against name, description and badges.
+    Phrase(String),
+    Tag(String),
+    Name(String),
+    Section(String),
+    Updated(DateOp, PartialDate),
+}
+
+/// Comparison in an `updated:` clause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DateOp {
+    Before,
+    AtOrBefore,
+    After,
+    AtOrAfter,
+    /// No operator: within the given year, month or day.
+    Within,
+}
+
+/// A year, month or day, as written in an `updated:` clause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PartialDate {
+    pub(crate) year: u16,
+    pub(crate) month: Option<u8>,
+    pub(crate) day: Option<u8>,
+}
+
+/// A calendar date as `(year, month, day)`, ordered chronologically.
+pub(crate) type Date = (u16, u8, u8);
+
+impl PartialDate {
+    /// First day of the period.
+    fn start(self) -> Date {
+        (self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+    }
+
+    /// Last day of the period (days past the end of a month never occur in data).
+    fn end(self) -> Date {
+        (self.year, self.month.unwrap_or(12), self.day.unwrap_or(31))
+    }
+
+    /// Parses `YYYY`, `YYYY-MM` or `YYYY-MM-DD`.
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split('-');
+        let year = parse_digits(parts.next()?, 4)?;
+        let mut next = || match parts.next() {
+            Some(part) => parse_digits(part, 2).map(Some),
+            None => Some(None),
+        };
+        let month = next()?;
+        let day = next()?;
+        if parts.next().is_some()
+            || month.is_some_and(|month| !(1..=12).contains(&month))
+            || day.is_some_and(|day| !(1..=31).contains(&day))
+        {
+            return None;
+        }
+        Some(Self {
+            year,
+            month: month.map(|month| month as u8),
+            day: day.map(|day| day as u8),
+        })
+    }
+}
+
+impl DateOp {
+    pub(crate) fn matches(self, date: Date, bound: PartialDate) -> bool {
+        match self {
+            DateOp::Before => date < bound.start(),
+            DateOp::AtOrBefore => date <= bound.end(),
+            DateOp::After => date > bound.end(),
+            DateOp::AtOrAfter => date >= bound.start(),
+            DateOp::Within => bound.start() <= date && date <= bound.end(),
+        }
+    }
+}
+
+/// Reads the date at the start of an `updated_at` value such as `2024-05-28`
+/// or `2024-05-28T09:00:00Z`.
+pub(crate) fn parse_date(text: &str) -> Option<Date> {
+    let date = PartialDate::parse(text.trim().get(..10)?)?;
+    Some((date.year, date.month?, date.day?))
+}
+
+fn parse_digits(text: &str, len: usize) -> Option<u16> {
+    if text.len() != len || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Why a query could not be parsed, with the byte range of the offending part.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct QueryError {
+    pub(crate) message: String,
+    pub(crate) span: Range<usize>,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+const FIELDS: [&str; 4] = ["tag", "name", "section", "updated"];
+
+/// Words one edit away from a field name that are more likely meant as text.
+const WORDS_NEAR_FIELDS: [&str; 18] = [
+    "came", "dame", "fame", "game", "lame", "same", "tame", "nape", "nave", "names", "named",
+    "tags", "stag", "sections", "suction", "update", "updates", "updater",
+];
+
+/// Whether `name:` is meant as a field: a known one or a likely typo of one.
+/// Anything else, like `game:` or the scheme of a pasted URL, is searched as text.
+fn looks_like_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    if FIELDS.contains(&name.as_str()) {
+        return true;
+    }
+    // 短い語や普通の単語は、たまたま 1 文字違いでも本文として探す
+    if name.len() < 4 || WORDS_NEAR_FIELDS.contains(&name.as_str()) {
+        return false;
+    }
+    let name: Vec<char> = name.chars().collect();
+    FIELDS.iter().any(|field| {
+        let field: Vec<char> = field.chars().collect();
+        edit_distance(&name, &field) <= 1
+    })
+}
+
+impl Query {
+    pub(crate) fn parse(input: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser { input, pos: 0 };
+        let mut clauses = Vec::new();
+        while let Some(clause) = parser.clause()? {
+            clauses.push(clause);
+        }
+        Ok(Self { clauses })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(message: impl Into<String>, span: Range<usize>) -> QueryError {
+        QueryError {
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Next clause, or `None` at the end of the input.
+    fn clause(&mut self) -> Result<Option<Clause>, QueryError> {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+
+        let start = self.pos;
+        let negated = trimmed.starts_with('-');
+        if negated {
+            self.pos += 1;
+            if self.rest().is_empty() || self.rest().starts_with(char::is_whitespace) {
+                return Err(Self::error(
+                    "「-」の後に除外する条件がありません",
+                    start..self.pos,
+                ));
+            }
+        }
+
+        let filter = if self.rest().starts_with('"') {
+            Filter::Phrase(self.quoted()?)
+        } else {
+            let word_start = self.pos;
+            let word = self.bare();
+            match word.split_once(':') {
+                Some((field, _))
+                    if field.chars().all(|c| c.is_ascii_alphabetic())
+                        && looks_like_field(field) =>
+                {
+                    self.pos = word_start + field.len() + 1;
+                    self.field(field, word_start)?
+                }
+                _ => Filter::Word(word.to_owned()),
+            }
+        };
+        Ok(Some(Clause { negated, filter }))
+    }
+
+    /// Reads up to the next whitespace.
+    fn bare(&mut self) -> &'a str {
+        let rest = self.rest();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    /// Reads a `"quoted"` string; the cursor is on the opening quote.
+    fn quoted(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        let body = &self.rest()[1..];
+        let Some(end) = body.find('"') else {
+            self.pos = self.input.len();
+            return Err(Self::error("引用符が閉じられていません", start..self.pos));
+        };
+        self.pos += end + 2;
+        let text = body[..end].trim();
+        if text.is_empty() {
+            return Err(Self::error("引用符の中が空です", start..self.pos));
+        }
+        Ok(text.to_owned())
+    }
+
+    /// Value of `field:`; the cursor is just past the colon.
+    fn field(&mut self, field: &str, start: usize) -> Result<Filter, QueryError> {
+        let value_start = self.pos;
+        let value = if self.rest().starts_with('"') {
+            self.quoted()?
+        } else {
+            self.bare().to_owned()
+        };
+        if value.is_empty() {
+            return Err(Self::error(
+                format!("「{field}:」の後に値がありません"),
+                start..self.pos,
+            ));
+        }
+
+        Ok(match field.to_ascii_lowercase().as_str() {
+            "tag" => Filter::Tag(value),
+            "name" => Filter::Name(value),
+            "section" => Filter::Section(value),
+            "updated" => {
+                // 2 文字の演算子を先に調べる
+                let (op, date) = [
+                    (">=", DateOp::AtOrAfter),
+                    ("<=", DateOp::AtOrBefore),
+                    (">", DateOp::After),
+                    ("<", DateOp::Before),
+                ]
+                .into_iter()
+                .find_map(|(prefix, op)| value.strip_prefix(prefix).map(|date| (op, date)))
+                .unwrap_or((DateOp::Within, value.as_str()));
+                let date = PartialDate::parse(date).ok_or_else(|| {
+                    Self::error(
+                        format!(
+                            "日付「{date}」は YYYY-MM-DD（または YYYY-MM、YYYY）の形式で指定してください"
+                        ),
+                        value_start..self.pos,
+                    )
+                })?;
+                Filter::Updated(op, date)
+            }
+            _ => {
+                return Err(Self::error(
+                    format!(
+                        "不明な検索フィールド「{field}:」です（使えるのは {}）",
+                        FIELDS.join(", ")
+                    ),
+                    start..value_start,
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<Clause> {
+        Query::parse(input)
+            .unwrap_or_else(|err| panic!("{input:?} should parse: {err}"))
+            .clauses
+    }
+
+    fn clause(filter: Filter) -> Clause {
+        Clause {
+            negated: false,
+            filter,
+        }
+    }
+
+    fn negated(filter: Filter) -> Clause {
+        Clause {
+            negated: true,
+            filter,
+        }
+    }
+
+    fn word(text: &str) -> Filter {
+        Filter::Word(text.to_string())
+    }
+
+    fn date(year: u16, month: Option<u8>, day: Option<u8>) -> PartialDate {
+        PartialDate { year, month, day }
+    }
+
+    /// 失敗したときのメッセージと、入力のうち指摘された部分
+    fn error(input: &str) -> (String, &str) {
+        let err = Query::parse(input).expect_err("query should be rejected");
+        (err.message, &input[err.span])
+    }
+
+    #[test]
+    fn empty_queries_have_no_clauses() {
+        assert!(Query::parse("").unwrap().is_empty());
+        assert!(Query::parse("   \t ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn words_are_separated_by_whitespace() {
+        assert_eq!(
+            parse("  egui   viewer\tc++ "),
+            [
+                clause(word("egui")),
+                clause(word("viewer")),
+                clause(word("c++"))
+            ]
+        );
+        // 記号や全角文字もそのまま語として扱う
+        assert_eq!(
+            parse("地図 v1.2"),
+            [clause(word("地図")), clause(word("v1.2"))]
+        );
+    }
+
+    #[test]
+    fn quoted_phrases_keep_spaces() {
+        assert_eq!(
+            parse(r#""machine learning" rust"#),
+            [
+                clause(Filter::Phrase("machine learning".to_string())),
+                clause(word("rust")),
+            ]
+        );
+        assert_eq!(
+            parse(r#"-"work in progress""#),
+            [negated(Filter::Phrase("work in progress".to_string()))]
+        );
+    }
+
+    #[test]
+    fn fields_take_bare_or_quoted_values() {
+        assert_eq!(
+            parse(r#"tag:rust name:foo section:"Tools and libs" TAG:Wasm"#),
+            [
+                clause(Filter::Tag("rust".to_string())),
+                clause(Filter::Name("foo".to_string())),
+                clause(Filter::Section("Tools and libs".to_string())),
+                clause(Filter::Tag("Wasm".to_string())),
+            ]
+        );
+        // 値の中のコロンはそのまま
+        assert_eq!(parse("name:a:b"), [clause(Filter::Name("a:b".to_string()))]);
+    }
+
+    #[test]
+    fn exclusions_apply_to_any_clause() {
+        assert_eq!(
+            parse("-python -tag:go rust"),
+            [
+                negated(word("python")),
+                negated(Filter::Tag("go".to_string())),
+                clause(word("rust")),
+            ]
+        );
+        // 語の途中のハイフンは除外ではない
+        assert_eq!(parse("map-panels"), [clause(word("map-panels"))]);
+    }
+
+    #[test]
+    fn words_with_non_field_colons_stay_words() {
+        assert_eq!(parse("c++:"), [clause(word("c++:"))]);
+        assert_eq!(parse(":rust"), [clause(word(":rust"))]);
+        assert_eq!(parse("v2:beta"), [clause(word("v2:beta"))]);
+        // フィールド名に似ていなければ英字だけでも語として扱う
+        assert_eq!(parse("owner:me"), [clause(word("owner:me"))]);
+        // 1 文字違いでも、普通の単語や短い語は誤字扱いしない
+        assert_eq!(parse("game:x"), [clause(word("game:x"))]);
+        assert_eq!(parse("date:x"), [clause(word("date:x"))]);
+        assert_eq!(parse("tags:x"), [clause(word("tags:x"))]);
+        assert_eq!(parse("tg:x"), [clause(word("tg:x"))]);
+        assert_eq!(
+            parse("https://github.com/pirakansa/map-panels"),
+            [clause(word("https://github.com/pirakansa/map-panels"))]
+        );
+    }
+
+    #[test]
+    fn updated_accepts_operators_and_partial_dates() {
+        let cases = [
+            (
+                "updated:>2024-01-01",
+                DateOp::After,
+                date(2024, Some(1), Some(1)),
+            ),
+            (
+                "updated:>=2024-01",
+                DateOp::AtOrAfter,
+                date(2024, Some(1), None),
+            ),
+            ("updated:<2023", DateOp::Before, date(2023, None, None)),
+            (
+                "updated:<=2023-12-31",
+                DateOp::AtOrBefore,
+                date(2023, Some(12), Some(31)),
+            ),
+            ("updated:2024-05", DateOp::Within, date(2024, Some(5), None)),
+        ];
+        for (input, op, bound) in cases {
+            assert_eq!(
+                parse(input),
+                [clause(Filter::Updated(op, bound))],
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn date_comparisons_use_the_whole_period() {
+        let may = date(2024, Some(5), None);
+        assert!(DateOp::Within.matches((2024, 5, 31), may));
+        assert!(!DateOp::Within.matches((2024, 6, 1), may));
+        assert!(DateOp::After.matches((2024, 6, 1), may));
+        assert!(!DateOp::After.matches((2024, 5, 31), may));
+        assert!(DateOp::AtOrAfter.matches((2024, 5, 1), may));
+        assert!(DateOp::Before.matches((2024, 4, 30), may));
+        assert!(!DateOp::Before.matches((2024, 5, 1), may));
+        assert!(DateOp::AtOrBefore.matches((2024, 5, 31), may));
+    }
+
+    #[test]
+    fn parse_date_reads_dates_and_timestamps() {
+        assert_eq!(parse_date("2024-05-28"), Some((2024, 5, 28)));
+        assert_eq!(parse_date(" 2024-05-28T09:00:00Z"), Some((2024, 5, 28)));
+        assert_eq!(parse_date("2024-05"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_part() {
+        let (message, span) = error(r#"rust "machine learning"#);
+        assert!(message.contains("引用符"), "{message}");
+        assert_eq!(span, r#""machine learning"#);
+
+        let (message, span) = error("rust tag: egui");
+        assert!(message.contains("tag:"), "{message}");
+        assert_eq!(span, "tag:");
+
+        let (message, span) = error("secton:tools");
+        assert!(
+            message.contains("secton:") && message.contains("section"),
+            "{message}"
+        );
+        assert_eq!(span, "secton:");
+        assert_eq!(error("nmae:foo").1, "nmae:");
+
+        let (message, span) = error("updated:>2024-13-01");
+        assert!(message.contains("YYYY-MM-DD"), "{message}");
+        assert_eq!(span, ">2024-13-01");
+
+        let (_, span) = error("updated:24-01-01");
+        assert_eq!(span, "24-01-01");
+
+        let (message, span) = error("rust -");
+        assert!(message.contains("除外"), "{message}");
+        assert_eq!(span, "-");
+
+        let (message, span) = error(r#"section:"""#);
+        assert!(message.contains("空"), "{message}");
+        assert_eq!(span, r#""""#);
+    }
+}
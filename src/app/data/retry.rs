@@ -0,0 +1,123 @@
+//! Retry scheduling for failed portfolio loads.
+//!
+//! Retries are driven from the frame loop instead of timers so the same code
+//! runs on the web, and time is read through [`Clock`] so tests can fake it.
+
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Monotonic time source used to schedule retries.
+pub(crate) trait Clock: Send + Sync {
+    /// Time elapsed since an arbitrary, fixed starting point.
+    fn now(&self) -> Duration;
+}
+
+/// Wall clock backed by `web_time::Instant`, which also works in the browser.
+pub(crate) struct SystemClock {
+    start: web_time::Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: web_time::Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Capped exponential backoff with jitter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct RetryPolicy {
+    /// Total number of automatic attempts, including the first one.
+    pub(crate) max_attempts: u32,
+    /// Delay after the first failure, before jitter.
+    pub(crate) base_delay: Duration,
+    /// Upper bound for the delay, before jitter.
+    pub(crate) max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether another automatic attempt follows the failed attempt number `attempt`.
+    pub(crate) fn should_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Delay before retrying after the failed attempt number `attempt` (1-based).
+    ///
+    /// Uses "equal jitter": half of the backoff is fixed and the other half is
+    /// scaled by `jitter` (expected in `0.0..=1.0`) to spread out clients.
+    pub(crate) fn delay(&self, attempt: u32, jitter: f32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let half = backoff / 2;
+        half + half.mul_f32(jitter.clamp(0.0, 1.0))
+    }
+}
+
+/// Random factor in `0.0..1.0` for [`RetryPolicy::delay`].
+pub(crate) fn jitter() -> f32 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    let since_epoch = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .unwrap_or_default();
+    hasher.write_u128(since_epoch.as_nanos());
+    (hasher.finish() >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_exponentially_until_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(8),
+        };
+        let delays: Vec<u64> = (1..=6).map(|a| policy.delay(a, 1.0).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 8, 8]);
+    }
+
+    #[test]
+    fn jitter_keeps_at_least_half_the_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(3, 0.0), Duration::from_secs(2));
+        assert_eq!(policy.delay(3, 0.5), Duration::from_secs(3));
+        assert_eq!(policy.delay(3, 7.0), Duration::from_secs(4));
+        for _ in 0..100 {
+            let value = jitter();
+            assert!((0.0..1.0).contains(&value), "jitter {value} out of range");
+        }
+    }
+
+    #[test]
+    fn should_retry_stops_at_max_attempts() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            ..Default::default()
+        };
+        assert!(policy.should_retry(1));
+        assert!(policy.should_retry(2));
+        assert!(!policy.should_retry(3));
+    }
+}
@@ -0,0 +1,726 @@
+//! Inverted index over the loaded repositories.
+//!
+//! Built once per portfolio snapshot. The folded words of every name and
+//! description, as well as each whole badge and the registered aliases of its
+//! tag, map to the items containing them.
+//! A query only scans this (much smaller) vocabulary for terms that contain
+//! each query word or are a likely typo of it, and scores just the items found
+//! there, instead of matching every field of every item. A word with symbols,
+//! such as `c++`, is first looked up whole so splitting does not widen it.
+//! `tag:` clauses are looked up in the badges directly; the remaining filters
+//! and exclusions of a [`Query`] are checked on the items found.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use super::fold::fold;
+use super::fuzzy::{match_exact, match_word, typo_match, FoldedText, MatchQuality};
+use super::query::{parse_date, Filter, Query};
+use super::{RepoSection, RepoSummary, Tag, TagRegistry};
+
+/// Position of an item: `(section index, item index)`.
+pub(crate) type ItemRef = (usize, usize);
+
+/// Postings are ordinals into `items`, ascending and without duplicates.
+type Postings = Vec<u32>;
+
+/// Field weights, chosen so that any name hit outranks any badge hit, which
+/// outranks any description hit.
+const NAME_WEIGHT: u32 = 30;
+const BADGE_WEIGHT: u32 = 20;
+const DESCRIPTION_WEIGHT: u32 = 10;
+
+/// An item matching a query, with what to highlight on its card.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SearchHit {
+    pub(crate) item: ItemRef,
+    /// Relevance; higher is better. Zero for the empty query.
+    pub(crate) score: u32,
+    /// Sorted, non-overlapping byte ranges of matched text in the name.
+    pub(crate) name: Vec<Range<usize>>,
+    /// Sorted, non-overlapping byte ranges of matched text in the description.
+    pub(crate) description: Vec<Range<usize>>,
+}
+
+impl SearchHit {
+    fn unranked(item: ItemRef) -> Self {
+        Self {
+            item,
+            score: 0,
+            name: Vec::new(),
+            description: Vec::new(),
+        }
+    }
+}
+
+struct Term {
+    chars: Vec<char>,
+    postings: Postings,
+}
+
+#[derive(Default)]
+pub(crate) struct SearchIndex {
+    items: Vec<ItemRef>,
+    /// Words of the names and descriptions.
+    terms: Vec<Term>,
+    /// Whole badges, e.g. `machine learning` or `c++`, and their aliases.
+    tags: BTreeMap<String, Postings>,
+    /// Registered aliases of the badges of each item, parallel to `items`.
+    /// Badges were rewritten to canonical names, so `wasm` would not find
+    /// `WebAssembly` otherwise.
+    aliases: Vec<Vec<String>>,
+}
+
+impl SearchIndex {
+    pub(crate) fn build(sections: &[RepoSection], registry: &TagRegistry) -> Self {
+        let mut index = SearchIndex::default();
+        let mut terms = BTreeMap::new();
+        for (section_index, section) in sections.iter().enumerate() {
+            for (item_index, repo) in section.items.iter().enumerate() {
+                let ordinal = index.items.len() as u32;
+                for field in [&repo.name, &repo.description] {
+                    for word in words(&fold(field)) {
+                        add_posting(&mut terms, word, ordinal);
+                    }
+                }
+                let aliases: Vec<String> = repo
+                    .badges
+                    .iter()
+                    .flat_map(|tag| registry.aliases(tag.key()))
+                    .cloned()
+                    .collect();
+                for tag in &repo.badges {
+                    add_posting(&mut index.tags, tag.key(), ordinal);
+                }
+                for alias in &aliases {
+                    add_posting(&mut index.tags, &fold(alias), ordinal);
+                }
+                index.items.push((section_index, item_index));
+                index.aliases.push(aliases);
+            }
+        }
+        index.terms = terms
+            .into_iter()
+            .map(|(term, postings)| Term {
+                chars: term.chars().collect(),
+                postings,
+            })
+            .collect();
+        index
+    }
+
+    /// Items of `sections` (the ones the index was built from) matching every
+    /// clause of `query`, in section and item order. An empty query matches everything.
+    pub(crate) fn search(&self, sections: &[RepoSection], query: &Query) -> Vec<SearchHit> {
+        if query.is_empty() {
+            return self
+                .items
+                .iter()
+                .copied()
+                .map(SearchHit::unranked)
+                .collect();
+        }
+
+        // 語・フレーズ・名前は点数を付け、タグ以外の絞り込みと除外は候補ごとに確かめる
+        let mut needles = Vec::new();
+        let mut checks = Vec::new();
+        let mut candidates: Option<Postings> = None;
+        let mut narrow = |postings: Postings| {
+            candidates = Some(match candidates.take() {
+                Some(current) => intersect(&current, &postings),
+                None => postings,
+            });
+        };
+        for clause in &query.clauses {
+            if clause.negated {
+                checks.push(clause);
+                continue;
+            }
+            match &clause.filter {
+                Filter::Word(word) => {
+                    let folded = fold(word);
+                    // 分割で変わる語（c++ が c になるなど）は、まず語全体で照合する
+                    if !words(&folded).eq([folded.as_str()]) {
+                        let whole = self.postings_whole(&folded);
+                        if !whole.is_empty() {
+                            narrow(whole);
+                            needles.push(Needle::exact(&folded, false));
+                            continue;
+                        }
+                    }
+                    let mut parts = words(&folded).peekable();
+                    if parts.peek().is_none() {
+                        // 記号だけの語は単語にならないので、全件をそのまま部分一致で調べる
+                        needles.push(Needle::exact(&folded, false));
+                    }
+                    for part in parts {
+                        let chars: Vec<char> = part.chars().collect();
+                        narrow(self.postings_matching(&chars));
+                        needles.push(Needle {
+                            chars,
+                            fuzzy: true,
+                            name_only: false,
+                        });
+                    }
+                }
+                Filter::Phrase(text) | Filter::Name(text) => {
+                    let folded = fold(text);
+                    for part in words(&folded) {
+                        let chars: Vec<char> = part.chars().collect();
+                        narrow(self.postings_matching(&chars));
+                    }
+                    let name_only = matches!(clause.filter, Filter::Name(_));
+                    needles.push(Needle::exact(&folded, name_only));
+                }
+                Filter::Tag(tag) => {
+                    narrow(self.tags.get(&fold(tag)).cloned().unwrap_or_default());
+                }
+                Filter::Section(_) | Filter::Updated(..) => checks.push(clause),
+            }
+        }
+
+        let candidates = candidates.unwrap_or_else(|| (0..self.items.len() as u32).collect());
+        candidates
+            .into_iter()
+            .map(|ordinal| {
+                (
+                    self.items[ordinal as usize],
+                    &self.aliases[ordinal as usize],
+                )
+            })
+            .filter(|&((section, item), aliases)| {
+                let section = &sections[section];
+                checks.iter().all(|clause| {
+                    let holds =
+                        clause_holds(section, &section.items[item], aliases, &clause.filter);
+                    holds != clause.negated
+                })
+            })
+            .filter_map(|(item, aliases)| score(sections, item, aliases, &needles))
+            .collect()
+    }
+
+    /// Items that may contain `word`, which has symbols such as `c++` or `v1.2`,
+    /// as written: those with it as a badge, or with each of its parts as a term.
+    fn postings_whole(&self, word: &str) -> Postings {
+        let mut parts = words(word).map(|part| {
+            self.terms
+                .binary_search_by(|term| term.chars.iter().copied().cmp(part.chars()))
+                .map(|found| self.terms[found].postings.clone())
+                .unwrap_or_default()
+        });
+        let mut postings = parts
+            .next()
+            .map(|first| parts.fold(first, |all, part| intersect(&all, &part)))
+            .unwrap_or_default();
+        postings.extend(self.tags.get(word).into_iter().flatten());
+        postings.sort_unstable();
+        postings.dedup();
+        postings
+    }
+
+    /// Union of the postings of every term or tag that contains `word` or may be a typo of it.
+    fn postings_matching(&self, word: &[char]) -> Postings {
+        let needle: String = word.iter().collect();
+        let terms = self
+            .terms
+            .iter()
+            .filter(|term| contains(&term.chars, word) || typo_match(word, &term.chars).is_some())
+            .map(|term| &term.postings);
+        let tags = self
+            .tags
+            .iter()
+            .filter(|(tag, _)| {
+                tag.contains(&needle)
+                    || words(tag).any(|tag_word| {
+                        typo_match(word, &tag_word.chars().collect::<Vec<_>>()).is_some()
+                    })
+            })
+            .map(|(_, postings)| postings);
+        let mut postings: Postings = terms.chain(tags).flatten().copied().collect();
+        postings.sort_unstable();
+        postings.dedup();
+        postings
+    }
+}
+
+/// Text a matching item must contain, scored by where and how well it occurs.
+struct Needle {
+    chars: Vec<char>,
+    /// Whether typos are tolerated.
+    fuzzy: bool,
+    /// Whether only the name is searched.
+    name_only: bool,
+}
+
+impl Needle {
+    fn exact(text: &str, name_only: bool) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            fuzzy: false,
+            name_only,
+        }
+    }
+
+    fn find(&self, text: &FoldedText<'_>) -> Option<(MatchQuality, Vec<Range<usize>>)> {
+        if self.fuzzy {
+            match_word(text, &self.chars)
+        } else {
+            match_exact(text, &self.chars)
+        }
+    }
+}
+
+/// Whether an unscored clause holds for `repo`, whose badges have the
+/// registered `aliases`, ignoring negation.
+/// Text clauses are matched exactly here, so an exclusion never drops an item
+/// just because it looks like a typo of the excluded word.
+fn clause_holds(
+    section: &RepoSection,
+    repo: &RepoSummary,
+    aliases: &[String],
+    filter: &Filter,
+) -> bool {
+    match filter {
+        Filter::Word(text) | Filter::Phrase(text) => {
+            let needle = fold(text);
+            [&repo.name, &repo.description]
+                .into_iter()
+                .map(String::as_str)
+                .chain(repo.badges.iter().map(Tag::label))
+                .chain(aliases.iter().map(String::as_str))
+                .any(|field| fold(field).contains(&needle))
+        }
+        Filter::Name(text) => fold(&repo.name).contains(&fold(text)),
+        Filter::Tag(tag) => {
+            let key = fold(tag);
+            repo.badges.iter().any(|badge| badge.key() == key)
+        }
+        Filter::Section(name) => fold(&section.name) == fold(name),
+        Filter::Updated(op, bound) => repo
+            .updated_at
+            .as_deref()
+            .and_then(parse_date)
+            .is_some_and(|date| op.matches(date, *bound)),
+    }
+}
+
+/// Scores one item, or `None` if some needle matches none of its fields.
+/// The `aliases` of its badges count as badges.
+fn score(
+    sections: &[RepoSection],
+    item: ItemRef,
+    aliases: &[String],
+    needles: &[Needle],
+) -> Option<SearchHit> {
+    let repo = &sections[item.0].items[item.1];
+    let name = FoldedText::new(&repo.name);
+    let description = FoldedText::new(&repo.description);
+    let badges: Vec<FoldedText<'_>> = repo
+        .badges
+        .iter()
+        .map(Tag::label)
+        .chain(aliases.iter().map(String::as_str))
+        .map(FoldedText::new)
+        .collect();
+
+    let mut hit = SearchHit::unranked(item);
+    for needle in needles {
+        let mut best = None;
+        if let Some((quality, ranges)) = needle.find(&name) {
+            best = best.max(Some(NAME_WEIGHT + quality.points()));
+            hit.name.extend(ranges);
+        }
+        if !needle.name_only {
+            // バッジごとに照合し、区切りをまたいで一致しないようにする
+            for badge in &badges {
+                if let Some((quality, _)) = needle.find(badge) {
+                    best = best.max(Some(BADGE_WEIGHT + quality.points()));
+                }
+            }
+            if let Some((quality, ranges)) = needle.find(&description) {
+                best = best.max(Some(DESCRIPTION_WEIGHT + quality.points()));
+                hit.description.extend(ranges);
+            }
+        }
+        hit.score += best?;
+    }
+    merge_ranges(&mut hit.name);
+    merge_ranges(&mut hit.description);
+    Some(hit)
+}
+
+fn merge_ranges(ranges: &mut Vec<Range<usize>>) {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+fn contains(haystack: &[char], needle: &[char]) -> bool {
+    needle.len() <= haystack.len()
+        && haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
+/// Alphanumeric runs of `text`. CJK text has no spaces, so a run may be a whole phrase.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+fn add_posting(map: &mut BTreeMap<String, Postings>, key: &str, ordinal: u32) {
+    let postings = map.entry(key.to_owned()).or_default();
+    // 項目は順に追加されるので、同じ項目の重複は末尾を見るだけで除ける
+    if postings.last() != Some(&ordinal) {
+        postings.push(ordinal);
+    }
+}
+
+fn intersect(a: &[u32], b: &[u32]) -> Postings {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RepoSummary, SectionMeta};
+    use super::*;
+
+    fn repo(name: &str, description: &str, badges: Option<&str>) -> RepoSummary {
+        RepoSummary {
+            name: name.to_string(),
+            description: description.to_string(),
+            image_url: None,
+            repo_url: format!("https://example.com/{name}"),
+            badges: badges.map(Tag::split).unwrap_or_default(),
+            updated_at: None,
+        }
+    }
+
+    fn sections() -> Vec<RepoSection> {
+        vec![
+            RepoSection {
+                name: "Tools".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![
+                    repo("map-panels", "Portfolio viewer in egui", Some("Rust, egui")),
+                    repo("ml-kit", "Training helpers", Some("machine learning, C++")),
+                    repo("tiles", "Renders map tiles", Some("Python")),
+                ],
+            },
+            RepoSection {
+                name: "日本語".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![repo("地図ビューア", "タイルを表示するツール", None)],
+            },
+        ]
+    }
+
+    fn hits(sections: &[RepoSection], query: &str) -> Vec<SearchHit> {
+        let query = Query::parse(query).expect("query should parse");
+        SearchIndex::build(sections, &TagRegistry::default()).search(sections, &query)
+    }
+
+    fn search(query: &str) -> Vec<ItemRef> {
+        hits(&sections(), query)
+            .into_iter()
+            .map(|hit| hit.item)
+            .collect()
+    }
+
+    fn hit(query: &str, item: ItemRef) -> SearchHit {
+        hits(&sections(), query)
+            .into_iter()
+            .find(|hit| hit.item == item)
+            .expect("item should match")
+    }
+
+    #[test]
+    fn every_query_word_must_match() {
+        assert_eq!(search("PANEL"), [(0, 0)]);
+        assert_eq!(search("viewer eg"), [(0, 0)]);
+        assert_eq!(search("egui viewer"), [(0, 0)]);
+        assert_eq!(search("表示"), [(1, 0)]);
+        assert!(search("viewer python").is_empty());
+    }
+
+    #[test]
+    fn typos_are_tolerated() {
+        assert_eq!(search("portfolo"), [(0, 0)]);
+        assert_eq!(search("machin lerning"), [(0, 1)]);
+        assert_eq!(search("pyhton"), [(0, 2)]);
+    }
+
+    #[test]
+    fn pasted_urls_search_as_text() {
+        let sections = vec![RepoSection {
+            name: "Links".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![
+                repo(
+                    "map-panels",
+                    "Mirror of https://github.com/pirakansa/map-panels",
+                    None,
+                ),
+                repo("other", "Hosted on https://example.com", None),
+            ],
+        }];
+        let items: Vec<ItemRef> = hits(&sections, "https://github.com/pirakansa/map-panels")
+            .into_iter()
+            .map(|hit| hit.item)
+            .collect();
+        assert_eq!(items, [(0, 0)]);
+    }
+
+    #[test]
+    fn tags_are_indexed_whole() {
+        assert_eq!(search("c++"), [(0, 1)]);
+        assert_eq!(search("machine learning"), [(0, 1)]);
+        assert_eq!(search("rust"), [(0, 0)]);
+
+        // "c++" を "c" として探すと cli まで一致してしまう
+        let mut sections = sections();
+        sections[0]
+            .items
+            .push(repo("cli", "Command line client", Some("Go")));
+        sections[0]
+            .items
+            .push(repo("engine", "Written in C++ and v1.2 ready", None));
+        let found = |query: &str| -> Vec<ItemRef> {
+            hits(&sections, query).iter().map(|hit| hit.item).collect()
+        };
+        assert_eq!(found("c++"), [(0, 1), (0, 4)]);
+        assert_eq!(found("v1.2"), [(0, 4)]);
+    }
+
+    #[test]
+    fn japanese_width_and_kana_variants_match() {
+        let sections = vec![RepoSection {
+            name: "ツール".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![
+                repo(
+                    "ラスト入門",
+                    "ＲＵＳＴで書いたｶﾞｲﾄﾞ",
+                    Some("Ｒｕｓｔ, ドキュメント"),
+                ),
+                repo("ぱねる", "ひらがなの名前", None),
+            ],
+        }];
+        let cases: &[(&str, &[ItemRef])] = &[
+            ("ﾗｽﾄ", &[(0, 0)]),
+            ("らすと", &[(0, 0)]),
+            ("rust", &[(0, 0)]),
+            ("ガイド", &[(0, 0)]),
+            ("がいど", &[(0, 0)]),
+            ("パネル", &[(0, 1)]),
+            ("ﾊﾟﾈﾙ", &[(0, 1)]),
+            ("ヒラガナ", &[(0, 1)]),
+            ("tag:RUST", &[(0, 0)]),
+            ("tag:どきゅめんと", &[(0, 0)]),
+            ("section:つーる", &[(0, 0), (0, 1)]),
+            ("name:ﾊﾟﾈﾙ", &[(0, 1)]),
+            ("-ﾊﾟﾈﾙ", &[(0, 0)]),
+        ];
+        for (query, expected) in cases {
+            let found: Vec<ItemRef> = hits(&sections, query).iter().map(|hit| hit.item).collect();
+            assert_eq!(found, *expected, "{query}");
+        }
+    }
+
+    #[test]
+    fn empty_and_symbol_only_queries() {
+        assert_eq!(search(""), [(0, 0), (0, 1), (0, 2), (1, 0)]);
+        assert_eq!(search("++"), [(0, 1)]);
+        assert!(search("==").is_empty());
+    }
+
+    fn dated_sections() -> Vec<RepoSection> {
+        let mut sections = sections();
+        let dates = ["2024-05-28", "2023-11-02T09:00:00Z", "2024-01-01"];
+        for (repo, date) in sections[0].items.iter_mut().zip(dates) {
+            repo.updated_at = Some(date.to_string());
+        }
+        sections
+    }
+
+    fn search_dated(query: &str) -> Vec<ItemRef> {
+        hits(&dated_sections(), query)
+            .into_iter()
+            .map(|hit| hit.item)
+            .collect()
+    }
+
+    #[test]
+    fn tag_clauses_match_whole_badges_only() {
+        assert_eq!(search("tag:RUST"), [(0, 0)]);
+        assert_eq!(search(r#"tag:"machine learning""#), [(0, 1)]);
+        assert!(search("tag:rus").is_empty(), "no partial tags");
+        assert!(search("tag:pyhton").is_empty(), "no typos in tags");
+        assert_eq!(search("tag:egui viewer"), [(0, 0)]);
+    }
+
+    #[test]
+    fn tag_clauses_do_not_match_longer_badges() {
+        let sections = vec![RepoSection {
+            name: "Tags".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![
+                repo("one", "", Some("Rust")),
+                repo("two", "", Some("Rusty, Go")),
+            ],
+        }];
+        let items = |query: &str| -> Vec<ItemRef> {
+            hits(&sections, query)
+                .into_iter()
+                .map(|hit| hit.item)
+                .collect()
+        };
+        assert_eq!(items("tag:rust"), [(0, 0)]);
+        assert_eq!(items("-tag:rust"), [(0, 1)]);
+        // 区切りをまたいだ "y, G" は一致しない
+        assert!(items(r#""y, g""#).is_empty());
+    }
+
+    #[test]
+    fn name_clauses_only_look_at_names() {
+        assert_eq!(search("name:map"), [(0, 0)]);
+        assert_eq!(search("map"), [(0, 0), (0, 2)]);
+        assert!(search("name:viewer").is_empty());
+        assert_eq!(search("name:地図"), [(1, 0)]);
+    }
+
+    #[test]
+    fn section_clauses_keep_one_section() {
+        assert_eq!(search("section:tools tile"), [(0, 2)]);
+        assert_eq!(search("section:日本語"), [(1, 0)]);
+        assert!(search(r#"section:"Other tools""#).is_empty());
+        assert!(
+            search("section:tool").is_empty(),
+            "section names match whole"
+        );
+    }
+
+    #[test]
+    fn phrases_match_exactly() {
+        assert_eq!(search(r#""map tiles""#), [(0, 2)]);
+        assert!(search(r#""tiles map""#).is_empty());
+        assert!(
+            search(r#""portfolo viewer""#).is_empty(),
+            "no typos in phrases"
+        );
+        assert_eq!(search(r#""in egui" panels"#), [(0, 0)]);
+    }
+
+    #[test]
+    fn exclusions_drop_exact_matches() {
+        assert_eq!(search("-python"), [(0, 0), (0, 1), (1, 0)]);
+        assert_eq!(search("map -tag:python"), [(0, 0)]);
+        assert_eq!(search("-section:tools"), [(1, 0)]);
+        assert_eq!(search(r#"-"machine learning" -地図"#), [(0, 0), (0, 2)]);
+        // 誤字の許容は除外には使わない
+        assert_eq!(search("-pyhton"), [(0, 0), (0, 1), (0, 2), (1, 0)]);
+    }
+
+    #[test]
+    fn updated_clauses_compare_dates() {
+        assert_eq!(search_dated("updated:>2024-01-01"), [(0, 0)]);
+        assert_eq!(search_dated("updated:>=2024-01-01"), [(0, 0), (0, 2)]);
+        assert_eq!(search_dated("updated:<2024"), [(0, 1)]);
+        assert_eq!(search_dated("updated:2024-05"), [(0, 0)]);
+        // 日付のない項目は比較に一致しないが、除外では残る
+        assert_eq!(search_dated("-updated:2024"), [(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn highlights_skip_unscored_clauses() {
+        let repo = &sections()[0].items[2];
+        let name = |hit: &SearchHit| -> Vec<String> {
+            hit.name
+                .iter()
+                .map(|r| repo.name[r.clone()].to_string())
+                .collect()
+        };
+        assert_eq!(name(&hit("tag:python tile", (0, 2))), ["tile"]);
+        let named = hit("name:tiles", (0, 2));
+        assert_eq!(name(&named), ["tiles"]);
+        assert!(
+            named.description.is_empty(),
+            "name: does not look at descriptions"
+        );
+    }
+
+    #[test]
+    fn name_hits_outrank_badge_hits_outrank_description_hits() {
+        // "map" は map-panels の名前と tiles の説明に含まれる
+        assert!(hit("map", (0, 0)).score > hit("map", (0, 2)).score);
+
+        let sections = vec![RepoSection {
+            name: "Ranking".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![
+                repo("plain", "Written in rust", None),
+                repo("tagged", "Nothing here", Some("Rust")),
+                repo("rust-tools", "Helpers", None),
+            ],
+        }];
+        let scores: Vec<u32> = hits(&sections, "rust")
+            .iter()
+            .map(|hit| hit.score)
+            .collect();
+        assert!(scores[2] > scores[1] && scores[1] > scores[0], "{scores:?}");
+
+        // 誤字でも名前の一致は説明の一致より上
+        let scores: Vec<u32> = hits(&sections, "rsut")
+            .iter()
+            .map(|hit| hit.score)
+            .collect();
+        assert!(scores[2] > scores[1] && scores[1] > scores[0], "{scores:?}");
+    }
+
+    #[test]
+    fn highlights_cover_matches_in_name_and_description() {
+        let hit = hit("map tile", (0, 2));
+        let repo = &sections()[0].items[2];
+        let name: Vec<&str> = hit.name.iter().map(|r| &repo.name[r.clone()]).collect();
+        let description: Vec<&str> = hit
+            .description
+            .iter()
+            .map(|r| &repo.description[r.clone()])
+            .collect();
+        assert_eq!(name, ["tile"]);
+        assert_eq!(description, ["map", "tile"]);
+    }
+
+    #[test]
+    fn merge_ranges_joins_overlaps() {
+        let mut ranges = vec![5..8, 0..2, 1..4, 8..9];
+        merge_ranges(&mut ranges);
+        assert_eq!(ranges, [0..4, 5..9]);
+    }
+
+    #[test]
+    fn intersect_keeps_common_postings() {
+        assert_eq!(intersect(&[1, 3, 5, 7], &[2, 3, 4, 7, 9]), [3, 7]);
+        assert!(intersect(&[], &[1]).is_empty());
+    }
+}
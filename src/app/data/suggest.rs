@@ -0,0 +1,381 @@
+//! Completions and recent queries offered under the search field.
+//!
+//! Completions come from the loaded snapshot: known badges, section names and
+//! repository names, matched against the word being typed after folding. A
+//! completion replaces that word with the matching `tag:`, `section:` or
+//! `name:` clause, keeping a leading `-` and any field already typed.
+
+use std::collections::HashSet;
+
+use super::fold::fold;
+use super::query::Query;
+use super::{RepoSection, Tag};
+
+/// Recent queries kept in [`SearchHistory`].
+const HISTORY_LEN: usize = 10;
+/// Recent queries listed while a query is being typed; all are listed for an empty field.
+const HISTORY_SHOWN: usize = 3;
+/// Entries in the dropdown.
+const SUGGESTION_LIMIT: usize = 8;
+
+/// Recently applied queries, newest first, persisted with the app state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub(crate) struct SearchHistory {
+    queries: Vec<String>,
+}
+
+impl SearchHistory {
+    /// Remembers `query` as the most recent one. Blank queries and queries
+    /// that do not parse are not remembered.
+    pub(crate) fn record(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() || Query::parse(query).is_err() {
+            return;
+        }
+        self.queries.retain(|recent| recent != query);
+        self.queries.insert(0, query.to_owned());
+        self.queries.truncate(HISTORY_LEN);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum SuggestionKind {
+    History,
+    Tag,
+    Section,
+    Repo,
+}
+
+impl SuggestionKind {
+    /// Field a completion of this kind fills in.
+    fn field(self) -> Option<&'static str> {
+        match self {
+            SuggestionKind::History => None,
+            SuggestionKind::Tag => Some("tag"),
+            SuggestionKind::Section => Some("section"),
+            SuggestionKind::Repo => Some("name"),
+        }
+    }
+}
+
+/// One entry of the dropdown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Suggestion {
+    pub(crate) kind: SuggestionKind,
+    /// What to show, e.g. the badge as written in the data.
+    pub(crate) label: String,
+    /// The whole search query after choosing this entry.
+    pub(crate) query: String,
+}
+
+struct Candidate {
+    kind: SuggestionKind,
+    text: String,
+    folded: String,
+}
+
+/// Badges, section names and repository names of a snapshot.
+#[derive(Default)]
+pub(crate) struct SearchSuggestions {
+    candidates: Vec<Candidate>,
+}
+
+impl SearchSuggestions {
+    pub(crate) fn build(sections: &[RepoSection]) -> Self {
+        let mut tags = Vec::new();
+        let mut names = Vec::new();
+        let mut repos = Vec::new();
+        for section in sections {
+            names.push(section.name.as_str());
+            for repo in &section.items {
+                repos.push(repo.name.as_str());
+                tags.extend(repo.badges.iter().map(Tag::label));
+            }
+        }
+
+        // 表記ゆれで同じになるものは最初に出てきた表記だけ残す
+        let mut seen = HashSet::new();
+        let candidates = [
+            (SuggestionKind::Tag, tags),
+            (SuggestionKind::Section, names),
+            (SuggestionKind::Repo, repos),
+        ]
+        .into_iter()
+        .flat_map(|(kind, texts)| texts.into_iter().map(move |text| (kind, text)))
+        .filter(|(_, text)| !text.is_empty())
+        .filter_map(|(kind, text)| {
+            let folded = fold(text);
+            seen.insert((kind, folded.clone())).then(|| Candidate {
+                kind,
+                text: text.to_owned(),
+                folded,
+            })
+        })
+        .collect();
+        Self { candidates }
+    }
+
+    /// Dropdown entries for the search field holding `input`: matching recent
+    /// queries first, then completions of the word being typed.
+    pub(crate) fn suggest(&self, input: &str, history: &SearchHistory) -> Vec<Suggestion> {
+        let typed = input.trim();
+        let folded_input = fold(typed);
+        let history_shown = if typed.is_empty() {
+            SUGGESTION_LIMIT
+        } else {
+            HISTORY_SHOWN
+        };
+        let mut suggestions: Vec<Suggestion> = history
+            .queries
+            .iter()
+            .filter(|query| query.as_str() != typed && fold(query).contains(&folded_input))
+            .take(history_shown)
+            .map(|query| Suggestion {
+                kind: SuggestionKind::History,
+                label: query.clone(),
+                query: query.clone(),
+            })
+            .collect();
+
+        if let Some(partial) = Partial::parse(input) {
+            let value = fold(partial.value);
+            let mut completions: Vec<(bool, &Candidate)> = self
+                .candidates
+                .iter()
+                .filter(|candidate| partial.kind.is_none_or(|kind| kind == candidate.kind))
+                // 入力済みの値と同じものは、種類を決める補完としてだけ出す
+                .filter(|candidate| partial.kind.is_none() || candidate.folded != value)
+                .filter_map(|candidate| {
+                    if candidate.folded.starts_with(&value) {
+                        Some((false, candidate))
+                    } else {
+                        candidate
+                            .folded
+                            .contains(&value)
+                            .then_some((true, candidate))
+                    }
+                })
+                .collect();
+            // 前方一致を先に、同じ種類の中では元の順序のまま
+            completions.sort_by_key(|(infix, candidate)| (*infix, candidate.kind));
+            suggestions.extend(
+                completions
+                    .into_iter()
+                    .filter_map(|(_, candidate)| partial.complete(candidate))
+                    .take(SUGGESTION_LIMIT),
+            );
+        }
+        suggestions.truncate(SUGGESTION_LIMIT);
+        suggestions
+    }
+}
+
+/// The word being typed at the end of the search field.
+struct Partial<'a> {
+    /// Everything before the word, kept as is.
+    before: &'a str,
+    negated: bool,
+    /// Kind named by a `field:` prefix, if any.
+    kind: Option<SuggestionKind>,
+    value: &'a str,
+}
+
+impl<'a> Partial<'a> {
+    fn parse(input: &'a str) -> Option<Self> {
+        if input.is_empty() || input.ends_with(char::is_whitespace) {
+            return None;
+        }
+        // 閉じていない引用符の中ならその引用符を含む語から、そうでなければ最後の空白の後から
+        let quoted = input.matches('"').count() % 2 == 1;
+        let search_end = if quoted {
+            input.rfind('"')?
+        } else {
+            input.len()
+        };
+        let start = input[..search_end]
+            .rfind(char::is_whitespace)
+            .map_or(0, |index| {
+                index + input[index..].chars().next().map_or(1, char::len_utf8)
+            });
+        let (before, word) = input.split_at(start);
+
+        let (negated, word) = match word.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, word),
+        };
+        let (kind, value) = match word.split_once(':') {
+            Some((field, value)) => {
+                let kind = match field.to_ascii_lowercase().as_str() {
+                    "tag" => SuggestionKind::Tag,
+                    "section" => SuggestionKind::Section,
+                    "name" => SuggestionKind::Repo,
+                    // updated: などは補完しない
+                    _ => return None,
+                };
+                (Some(kind), value.strip_prefix('"').unwrap_or(value))
+            }
+            // フレーズは補完しない
+            None if word.starts_with('"') => return None,
+            None if word.is_empty() => return None,
+            None => (None, word),
+        };
+        if value.contains('"') {
+            return None;
+        }
+        Some(Self {
+            before,
+            negated,
+            kind,
+            value,
+        })
+    }
+
+    /// The query with the word replaced by a clause for `candidate`.
+    fn complete(&self, candidate: &Candidate) -> Option<Suggestion> {
+        let field = candidate.kind.field()?;
+        let text = &candidate.text;
+        if text.contains('"') {
+            return None;
+        }
+        let value = if text.contains(char::is_whitespace) {
+            format!("\"{text}\"")
+        } else {
+            text.clone()
+        };
+        let minus = if self.negated { "-" } else { "" };
+        Some(Suggestion {
+            kind: candidate.kind,
+            label: text.clone(),
+            query: format!("{}{minus}{field}:{value} ", self.before),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{RepoSummary, SectionMeta};
+    use super::*;
+
+    fn repo(name: &str, badges: &str) -> RepoSummary {
+        RepoSummary {
+            name: name.to_string(),
+            description: String::new(),
+            image_url: None,
+            repo_url: format!("https://example.com/{name}"),
+            badges: Tag::split(badges),
+            updated_at: None,
+        }
+    }
+
+    fn suggestions() -> SearchSuggestions {
+        SearchSuggestions::build(&[
+            RepoSection {
+                name: "Tools and libs".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![
+                    repo("rust-map", "Rust, egui"),
+                    repo("ml-kit", "machine learning, rust"),
+                ],
+            },
+            RepoSection {
+                name: "ラボ".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![repo("runner", "ラスト, Ruby")],
+            },
+        ])
+    }
+
+    fn queries(input: &str, history: &SearchHistory) -> Vec<String> {
+        suggestions()
+            .suggest(input, history)
+            .into_iter()
+            .map(|suggestion| suggestion.query)
+            .collect()
+    }
+
+    fn history(queries: &[&str]) -> SearchHistory {
+        let mut history = SearchHistory::default();
+        for query in queries.iter().rev() {
+            history.record(query);
+        }
+        history
+    }
+
+    #[test]
+    fn history_keeps_recent_distinct_valid_queries() {
+        let mut history = SearchHistory::default();
+        history.record("rust");
+        history.record("  egui ");
+        history.record("rust");
+        history.record("");
+        history.record("tag:");
+        assert_eq!(history.queries, ["rust", "egui"]);
+
+        for index in 0..HISTORY_LEN + 2 {
+            history.record(&format!("query {index}"));
+        }
+        assert_eq!(history.queries.len(), HISTORY_LEN);
+        assert_eq!(history.queries[0], format!("query {}", HISTORY_LEN + 1));
+    }
+
+    #[test]
+    fn completes_the_last_word_with_a_clause() {
+        assert_eq!(
+            queries("ru", &SearchHistory::default()),
+            ["tag:Rust ", "tag:Ruby ", "name:rust-map ", "name:runner "]
+        );
+        // 前方一致の後に部分一致
+        assert_eq!(
+            queries("egui ma", &SearchHistory::default()),
+            ["egui tag:\"machine learning\" ", "egui name:rust-map "]
+        );
+    }
+
+    #[test]
+    fn field_prefixes_restrict_and_keep_exclusions() {
+        assert_eq!(
+            queries("tag:", &SearchHistory::default()),
+            [
+                "tag:Rust ",
+                "tag:egui ",
+                "tag:\"machine learning\" ",
+                "tag:ラスト ",
+                "tag:Ruby "
+            ]
+        );
+        assert_eq!(
+            queries("-section:\"tools a", &SearchHistory::default()),
+            ["-section:\"Tools and libs\" "]
+        );
+        assert_eq!(
+            queries("name:kit", &SearchHistory::default()),
+            ["name:ml-kit "]
+        );
+        assert!(queries("updated:20", &SearchHistory::default()).is_empty());
+        assert!(queries("\"ru", &SearchHistory::default()).is_empty());
+        assert!(queries("tag:Rust ", &SearchHistory::default()).is_empty());
+    }
+
+    #[test]
+    fn completions_match_folded_text() {
+        assert_eq!(queries("らす", &SearchHistory::default()), ["tag:ラスト "]);
+        assert_eq!(queries("ﾗﾎﾞ", &SearchHistory::default()), ["section:ラボ "]);
+    }
+
+    #[test]
+    fn recent_queries_come_first() {
+        let history = history(&["tag:rust egui", "viewer", "rust"]);
+        assert_eq!(queries("", &history), ["tag:rust egui", "viewer", "rust"]);
+        let found = suggestions().suggest("rus", &history);
+        assert_eq!(found[0].kind, SuggestionKind::History);
+        assert_eq!(found[0].query, "tag:rust egui");
+        assert_eq!(found[1].query, "rust");
+        assert_eq!(found[2].query, "tag:Rust ");
+        // 入力と同じ履歴は出さない
+        assert!(suggestions()
+            .suggest("rust", &history)
+            .iter()
+            .all(|suggestion| suggestion.query != "rust"));
+    }
+}
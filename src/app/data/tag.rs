@@ -0,0 +1,419 @@
+//! Badges of a repository, normalized once when the portfolio is loaded.
+//!
+//! Portfolio files may give `badges` either as a list or, as older files do,
+//! as one comma-separated string:
+//!
+//! ```yaml
+//! badges: [Rust, WebAssembly]
+//! badges: "Rust, WebAssembly"
+//! ```
+//!
+//! Both become the same [`Tag`]s: trimmed, without empty entries, and without
+//! entries that only differ in [folding](super::fold). Badges are written back
+//! as a comma-separated string so older versions keep reading the files.
+//!
+//! The [`TagRegistry`] from the app config goes further and maps aliases such
+//! as `RustLang` or `WASM` to one canonical name, with a colour and icon for
+//! its chips:
+//!
+//! ```yaml
+//! tags:
+//!   - name: WebAssembly
+//!     aliases: [WASM]
+//!     color: "#654ff0"
+//!     icon: 🧩
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+use super::fold::fold;
+use super::query::{Filter, Query};
+use super::RepoSection;
+
+/// One badge, as written in the data and folded for comparisons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Tag {
+    label: String,
+    key: String,
+}
+
+impl Tag {
+    pub(crate) fn new(label: &str) -> Self {
+        let label = label.trim();
+        Self {
+            label: label.to_owned(),
+            key: fold(label),
+        }
+    }
+
+    /// The badge as written in the data.
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The folded badge, compared exactly by filters and facets.
+    pub(crate) fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Splits the legacy comma-separated form, e.g. `"Rust, WASM"`.
+    pub(crate) fn split(text: &str) -> Vec<Tag> {
+        normalize(text.split(',').map(Tag::new))
+    }
+}
+
+/// Drops empty tags and repeats, keeping the first spelling.
+fn normalize(all: impl IntoIterator<Item = Tag>) -> Vec<Tag> {
+    let mut tags: Vec<Tag> = Vec::new();
+    for tag in all {
+        if !tag.key.is_empty() && !tags.iter().any(|seen| seen.key == tag.key) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// One entry of `tags` in the app config.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) struct TagDefinition {
+    /// Canonical name, shown wherever the tag or one of its aliases appears.
+    pub(crate) name: String,
+    /// Other spellings of the tag. Case and width variants need not be listed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) aliases: Vec<String>,
+    /// Chip colour as `#rrggbb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<String>,
+    /// Shown before the name on chips, e.g. an emoji.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) icon: Option<String>,
+}
+
+/// How the chips of a registered tag are drawn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TagStyle {
+    pub(crate) color: Option<egui::Color32>,
+    pub(crate) icon: Option<String>,
+}
+
+/// Canonical tags and their aliases, read from the app config.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "Vec<TagDefinition>", into = "Vec<TagDefinition>")]
+pub(crate) struct TagRegistry {
+    definitions: Vec<TagDefinition>,
+    /// Parallel to `definitions`.
+    styles: Vec<TagStyle>,
+    /// Folded names and aliases to the index of their definition.
+    lookup: HashMap<String, usize>,
+}
+
+impl TryFrom<Vec<TagDefinition>> for TagRegistry {
+    type Error = String;
+
+    fn try_from(definitions: Vec<TagDefinition>) -> Result<Self, Self::Error> {
+        let mut lookup: HashMap<String, usize> = HashMap::new();
+        let mut styles = Vec::with_capacity(definitions.len());
+        for (index, definition) in definitions.iter().enumerate() {
+            if definition.name.trim().is_empty() {
+                return Err("tag name must not be empty".to_owned());
+            }
+            for spelling in std::iter::once(&definition.name).chain(&definition.aliases) {
+                let key = fold(spelling.trim());
+                if let Some(&other) = lookup.get(&key) {
+                    if other != index {
+                        return Err(format!(
+                            "\"{spelling}\" is listed for both \"{}\" and \"{}\"",
+                            definitions[other].name, definition.name
+                        ));
+                    }
+                }
+                lookup.insert(key, index);
+            }
+            let color = definition
+                .color
+                .as_deref()
+                .map(|hex| {
+                    egui::Color32::from_hex(hex.trim()).map_err(|_| {
+                        format!("invalid color \"{hex}\" for tag \"{}\"", definition.name)
+                    })
+                })
+                .transpose()?;
+            styles.push(TagStyle {
+                color,
+                icon: definition.icon.clone(),
+            });
+        }
+        Ok(Self {
+            definitions,
+            styles,
+            lookup,
+        })
+    }
+}
+
+impl From<TagRegistry> for Vec<TagDefinition> {
+    fn from(registry: TagRegistry) -> Self {
+        registry.definitions
+    }
+}
+
+impl TagRegistry {
+    /// The tag `label` stands for: its canonical name if registered, else itself.
+    pub(crate) fn resolve(&self, label: &str) -> Tag {
+        match self.lookup.get(&fold(label.trim())) {
+            Some(&index) => Tag::new(&self.definitions[index].name),
+            None => Tag::new(label),
+        }
+    }
+
+    /// Other spellings of the tag with the folded text `key`, empty if it is not registered.
+    pub(crate) fn aliases(&self, key: &str) -> &[String] {
+        self.lookup
+            .get(key)
+            .map_or(&[], |&index| &self.definitions[index].aliases)
+    }
+
+    /// Chip style of the tag with the folded text `key`, if it is registered.
+    pub(crate) fn style(&self, key: &str) -> Option<&TagStyle> {
+        self.lookup.get(key).map(|&index| &self.styles[index])
+    }
+
+    /// Replaces aliases in the badges of every item by their canonical tags,
+    /// merging badges that turn out to be the same.
+    pub(crate) fn apply(&self, sections: Arc<[RepoSection]>) -> Arc<[RepoSection]> {
+        if self.lookup.is_empty() {
+            return sections;
+        }
+        sections
+            .iter()
+            .map(|section| {
+                let mut section = section.clone();
+                for repo in &mut section.items {
+                    repo.badges =
+                        normalize(repo.badges.iter().map(|tag| self.resolve(tag.label())));
+                }
+                section
+            })
+            .collect()
+    }
+
+    /// Rewrites the `tag:` clauses of `query` to canonical names. Plain words
+    /// are left as typed; the search index also finds badges by their aliases.
+    pub(crate) fn resolve_query(&self, query: &mut Query) {
+        for clause in &mut query.clauses {
+            if let Filter::Tag(tag) = &mut clause.filter {
+                *tag = self.resolve(tag).label;
+            }
+        }
+    }
+}
+
+/// `#[serde(with = "tag::badges")]` for `Vec<Tag>` fields.
+pub(crate) mod badges {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(tags: &[Tag], serializer: S) -> Result<S::Ok, S::Error> {
+        // カンマを含むタグは文字列にすると分かれてしまうので、その場合だけ配列で書く
+        if tags.iter().any(|tag| tag.label.contains(',')) {
+            serializer.collect_seq(tags.iter().map(Tag::label))
+        } else {
+            let labels: Vec<&str> = tags.iter().map(Tag::label).collect();
+            serializer.serialize_str(&labels.join(", "))
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Tag>, D::Error> {
+        deserializer.deserialize_any(BadgesVisitor)
+    }
+}
+
+struct BadgesVisitor;
+
+impl<'de> Visitor<'de> for BadgesVisitor {
+    type Value = Vec<Tag>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a list of badges or a comma-separated string")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Self::Value, E> {
+        Ok(Tag::split(text))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut labels = Vec::new();
+        while let Some(label) = seq.next_element::<String>()? {
+            labels.push(label);
+        }
+        Ok(normalize(labels.iter().map(|label| Tag::new(label))))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Vec::new())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Vec::new())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    struct Item {
+        #[serde(default, with = "badges", skip_serializing_if = "Vec::is_empty")]
+        badges: Vec<Tag>,
+    }
+
+    fn labels(tags: &[Tag]) -> Vec<&str> {
+        tags.iter().map(Tag::label).collect()
+    }
+
+    #[test]
+    fn list_and_comma_string_read_the_same() {
+        let list: Item = serde_yaml::from_str("badges: [Rust, ' WASM ', '']").unwrap();
+        let text: Item = serde_yaml::from_str("badges: 'Rust, WASM,'").unwrap();
+        assert_eq!(labels(&list.badges), ["Rust", "WASM"]);
+        assert_eq!(list, text);
+
+        let json: Item = serde_json::from_str(r#"{"badges": ["Rust", "WASM"]}"#).unwrap();
+        assert_eq!(json, text);
+        let toml: Item = toml::from_str(r#"badges = "Rust, WASM""#).unwrap();
+        assert_eq!(toml, text);
+    }
+
+    #[test]
+    fn missing_or_null_badges_are_empty() {
+        for yaml in ["{}", "badges: null", "badges: ''", "badges: []"] {
+            let item: Item = serde_yaml::from_str(yaml).unwrap();
+            assert!(item.badges.is_empty(), "{yaml}");
+        }
+    }
+
+    #[test]
+    fn folded_repeats_keep_the_first_spelling() {
+        let tags = Tag::split("Rust, rust, ＲＵＳＴ, egui");
+        assert_eq!(labels(&tags), ["Rust", "egui"]);
+        assert_eq!(tags[0].key(), "rust");
+    }
+
+    #[test]
+    fn list_items_may_contain_commas() {
+        let item: Item = serde_yaml::from_str("badges: ['a, b', c]").unwrap();
+        assert_eq!(labels(&item.badges), ["a, b", "c"]);
+        let yaml = serde_yaml::to_string(&item).unwrap();
+        assert_eq!(serde_yaml::from_str::<Item>(&yaml).unwrap(), item);
+    }
+
+    #[test]
+    fn serializes_as_the_legacy_string() {
+        let item = Item {
+            badges: Tag::split("Rust,WASM"),
+        };
+        assert_eq!(
+            serde_json::to_string(&item).unwrap(),
+            r#"{"badges":"Rust, WASM"}"#
+        );
+    }
+
+    fn registry(yaml: &str) -> Result<TagRegistry, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    const REGISTRY: &str = "\
+- name: Rust
+  aliases: [RustLang, rust-lang]
+  color: '#dea584'
+  icon: 🦀
+- name: WebAssembly
+  aliases: [WASM]
+";
+
+    #[test]
+    fn aliases_resolve_to_the_canonical_name() {
+        let registry = registry(REGISTRY).unwrap();
+        for label in ["rust", "ＲＵＳＴ", "RustLang", "rust-lang"] {
+            assert_eq!(registry.resolve(label).label(), "Rust", "{label}");
+        }
+        assert_eq!(registry.resolve("wasm").key(), "webassembly");
+        assert_eq!(registry.resolve(" Go ").label(), "Go");
+
+        let style = registry.style("rust").unwrap();
+        assert_eq!(style.color, Some(egui::Color32::from_rgb(0xde, 0xa5, 0x84)));
+        assert_eq!(style.icon.as_deref(), Some("🦀"));
+        assert_eq!(registry.style("webassembly").unwrap().color, None);
+        assert!(registry.style("go").is_none());
+    }
+
+    #[test]
+    fn apply_merges_aliases_of_one_tag() {
+        use super::super::{RepoSummary, SectionMeta};
+
+        let registry = registry(REGISTRY).unwrap();
+        let sections: Arc<[RepoSection]> = Arc::new([RepoSection {
+            name: "Tools".to_string(),
+            meta: SectionMeta::default(),
+            items: vec![RepoSummary {
+                name: "viewer".to_string(),
+                description: String::new(),
+                image_url: None,
+                repo_url: "https://example.com".to_string(),
+                badges: Tag::split("RustLang, WASM, rust, egui"),
+                updated_at: None,
+            }],
+        }]);
+        let applied = registry.apply(Arc::clone(&sections));
+        assert_eq!(
+            labels(&applied[0].items[0].badges),
+            ["Rust", "WebAssembly", "egui"]
+        );
+
+        let empty = TagRegistry::default().apply(Arc::clone(&sections));
+        assert!(Arc::ptr_eq(&empty, &sections), "nothing to resolve");
+    }
+
+    #[test]
+    fn tag_clauses_are_resolved() {
+        let registry = registry(REGISTRY).unwrap();
+        let mut query = Query::parse("tag:wasm -tag:rustlang wasm").unwrap();
+        registry.resolve_query(&mut query);
+        let filters: Vec<&Filter> = query.clauses.iter().map(|clause| &clause.filter).collect();
+        assert_eq!(
+            filters,
+            [
+                &Filter::Tag("WebAssembly".to_string()),
+                &Filter::Tag("Rust".to_string()),
+                &Filter::Word("wasm".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_registries_are_rejected() {
+        assert!(registry("- name: Rust\n  color: orange\n").is_err());
+        assert!(registry("- name: ' '\n").is_err());
+        let shared = registry("- name: Go\n  aliases: [golang]\n- name: Golang\n");
+        let message = shared.unwrap_err().to_string();
+        assert!(message.contains(r#"both "Go" and "Golang""#), "{message}");
+        assert!(registry("- name: Go\n  aliases: [go, GO]\n").is_ok());
+    }
+
+    #[test]
+    fn other_values_are_rejected() {
+        assert!(serde_yaml::from_str::<Item>("badges: 3").is_err());
+        assert!(serde_yaml::from_str::<Item>("badges: {a: b}").is_err());
+    }
+}
@@ -0,0 +1,478 @@
+//! Search results over a shared portfolio snapshot.
+//!
+//! The loaded sections are shared as an `Arc<[RepoSection]>`, so the view only
+//! stores the hits of the matching items, recomputed when the snapshot, the
+//! query, the selected badges or the result order changes and reused on every
+//! other frame. A new snapshot also rebuilds the [`SearchIndex`] and the
+//! [`SearchSuggestions`].
+//!
+//! `tag:` clauses are resolved through the [`TagRegistry`] so aliases find the
+//! canonical badges. A query that fails to parse keeps filtering by the last
+//! valid one and reports the error instead. Badge counts for the facet panel
+//! are taken before the selected badges narrow the hits down further.
+
+use std::sync::Arc;
+
+use super::facets::{count_tags, TagCount, TagSelection};
+use super::query::{Query, QueryError};
+use super::search::{SearchHit, SearchIndex};
+use super::suggest::SearchSuggestions;
+use super::tag::TagRegistry;
+use super::{RepoSection, RepoSummary};
+use crate::app::settings::ResultOrder;
+
+/// Sections narrowed down to the items matching the search query.
+#[derive(Default)]
+pub(crate) struct FilteredSections {
+    registry: TagRegistry,
+    sections: Arc<[RepoSection]>,
+    index: SearchIndex,
+    suggestions: SearchSuggestions,
+    query: String,
+    tags: TagSelection,
+    order: ResultOrder,
+    /// The last query that parsed, used while `query` has an error.
+    parsed: Query,
+    error: Option<QueryError>,
+    /// `(section index, hits)` for sections with at least one match, in display order.
+    matches: Vec<(usize, Vec<SearchHit>)>,
+    facets: Vec<TagCount>,
+}
+
+impl FilteredSections {
+    pub(crate) fn new(registry: TagRegistry) -> Self {
+        Self {
+            registry,
+            ..Self::default()
+        }
+    }
+
+    /// Filters `sections` by `query` and `tags` unless the result for these inputs is already cached.
+    ///
+    /// Returns `true` if the view or its [`query_error`](Self::query_error) changed.
+    pub(crate) fn update(
+        &mut self,
+        sections: &Arc<[RepoSection]>,
+        query: &str,
+        tags: &TagSelection,
+        order: ResultOrder,
+    ) -> bool {
+        let same_snapshot = Arc::ptr_eq(&self.sections, sections);
+        if same_snapshot && self.query == query && self.tags == *tags && self.order == order {
+            return false;
+        }
+        if !same_snapshot {
+            self.index = SearchIndex::build(sections, &self.registry);
+            self.suggestions = SearchSuggestions::build(sections);
+            self.sections = Arc::clone(sections);
+        }
+        query.clone_into(&mut self.query);
+        self.tags.clone_from(tags);
+        self.order = order;
+        // 入力途中の誤りで結果が消えないよう、最後に解釈できたクエリで絞り込む
+        match Query::parse(query) {
+            Ok(mut parsed) => {
+                self.registry.resolve_query(&mut parsed);
+                self.parsed = parsed;
+                self.error = None;
+            }
+            Err(error) => self.error = Some(error),
+        }
+        let hits = self.index.search(sections, &self.parsed);
+        let repo = |hit: &SearchHit| &sections[hit.item.0].items[hit.item.1];
+        self.facets = count_tags(hits.iter().map(repo), tags, &self.registry);
+        self.matches.clear();
+        for hit in hits.into_iter().filter(|hit| tags.matches(repo(hit))) {
+            match self.matches.last_mut() {
+                Some((section, hits)) if *section == hit.item.0 => hits.push(hit),
+                _ => self.matches.push((hit.item.0, vec![hit])),
+            }
+        }
+        if order == ResultOrder::Relevance {
+            // 同点の場合は元の順序を保つ（安定ソート）
+            for (_, hits) in &mut self.matches {
+                hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+            }
+            self.matches
+                .sort_by_key(|(_, hits)| std::cmp::Reverse(hits[0].score));
+        }
+        true
+    }
+
+    /// Why the current query could not be parsed, if it could not.
+    pub(crate) fn query_error(&self) -> Option<&QueryError> {
+        self.error.as_ref()
+    }
+
+    /// Badges of the items matching the query, with counts, for the facet panel.
+    pub(crate) fn facets(&self) -> &[TagCount] {
+        &self.facets
+    }
+
+    /// Completions drawn from the current snapshot.
+    pub(crate) fn suggestions(&self) -> &SearchSuggestions {
+        &self.suggestions
+    }
+
+    /// Sections that have at least one matching item, in display order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = SectionView<'_>> {
+        self.matches
+            .iter()
+            .map(|(section_index, hits)| SectionView {
+                section: &self.sections[*section_index],
+                hits,
+            })
+    }
+}
+
+/// One section of a [`FilteredSections`] view.
+#[derive(Clone, Copy)]
+pub(crate) struct SectionView<'a> {
+    pub(crate) section: &'a RepoSection,
+    hits: &'a [SearchHit],
+}
+
+impl<'a> SectionView<'a> {
+    /// Matching items of the section in display order, with what matched in each.
+    pub(crate) fn items(&self) -> impl Iterator<Item = (&'a RepoSummary, &'a SearchHit)> + 'a {
+        let section = self.section;
+        self.hits
+            .iter()
+            .map(move |hit| (&section.items[hit.item.1], hit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::facets::TagMatch;
+    use super::super::{SectionMeta, Tag};
+    use super::*;
+
+    fn repo(name: &str, description: &str, badges: &str) -> RepoSummary {
+        RepoSummary {
+            name: name.to_string(),
+            description: description.to_string(),
+            image_url: None,
+            repo_url: format!("https://example.com/{name}"),
+            badges: Tag::split(badges),
+            updated_at: None,
+        }
+    }
+
+    fn sections() -> Arc<[RepoSection]> {
+        Arc::from(vec![
+            RepoSection {
+                name: "Test Section".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![
+                    repo("Rust Project", "A Rust library", "rust, wasm"),
+                    repo("Python Project", "A Python tool", "python"),
+                ],
+            },
+            RepoSection {
+                name: "Other".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![repo("Scripts", "Shell helpers", "python, bash")],
+            },
+        ])
+    }
+
+    fn names(view: &FilteredSections) -> Vec<(&str, Vec<&str>)> {
+        view.iter()
+            .map(|section| {
+                (
+                    section.section.name.as_str(),
+                    section
+                        .items()
+                        .map(|(item, _)| item.name.as_str())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filters_items_and_hides_empty_sections() {
+        let sections = sections();
+        let mut view = FilteredSections::default();
+
+        view.update(
+            &sections,
+            "rust",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(names(&view), [("Test Section", vec!["Rust Project"])]);
+
+        view.update(
+            &sections,
+            "PYTHON",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+
+        view.update(&sections, "", &TagSelection::default(), ResultOrder::Source);
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Rust Project", "Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn relevance_order_puts_best_matches_first() {
+        let sections: Arc<[RepoSection]> = Arc::from(vec![
+            RepoSection {
+                name: "Docs".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![
+                    repo("guide", "Notes about the egui viewer", "docs"),
+                    repo("cookbook", "Recipes", "egui"),
+                ],
+            },
+            RepoSection {
+                name: "Apps".to_string(),
+                meta: SectionMeta::default(),
+                items: vec![repo("egui-viewer", "Portfolio viewer", "rust")],
+            },
+        ]);
+        let mut view = FilteredSections::default();
+
+        view.update(
+            &sections,
+            "egui",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(
+            names(&view),
+            [
+                ("Docs", vec!["guide", "cookbook"]),
+                ("Apps", vec!["egui-viewer"]),
+            ]
+        );
+
+        // 名前の一致 > バッジの一致 > 説明の一致。セクションも最良の一致順に並ぶ
+        view.update(
+            &sections,
+            "egui",
+            &TagSelection::default(),
+            ResultOrder::Relevance,
+        );
+        assert_eq!(
+            names(&view),
+            [
+                ("Apps", vec!["egui-viewer"]),
+                ("Docs", vec!["cookbook", "guide"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_queries_keep_previous_matches() {
+        let sections = sections();
+        let mut view = FilteredSections::default();
+        view.update(
+            &sections,
+            "tag:python",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert!(view.query_error().is_none());
+
+        assert!(view.update(
+            &sections,
+            "tag:python section:",
+            &TagSelection::default(),
+            ResultOrder::Source
+        ));
+        let error = view
+            .query_error()
+            .expect("missing value should be reported");
+        assert_eq!(error.span, 11..19);
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+
+        view.update(
+            &sections,
+            "tag:python section:other",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert!(view.query_error().is_none());
+        assert_eq!(names(&view), [("Other", vec!["Scripts"])]);
+    }
+
+    #[test]
+    fn tag_clauses_resolve_aliases_to_canonical_badges() {
+        let registry: TagRegistry =
+            serde_yaml::from_str("- name: WebAssembly\n  aliases: [WASM]\n").unwrap();
+        let sections = registry.apply(sections());
+        let mut view = FilteredSections::new(registry);
+
+        view.update(
+            &sections,
+            "tag:wasm",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(names(&view), [("Test Section", vec!["Rust Project"])]);
+        assert!(view
+            .facets()
+            .iter()
+            .any(|tag| tag.label == "WebAssembly" && tag.count == 1));
+    }
+
+    #[test]
+    fn words_still_find_badges_renamed_by_an_alias() {
+        let registry: TagRegistry =
+            serde_yaml::from_str("- name: WebAssembly\n  aliases: [WASM]\n").unwrap();
+        let sections = registry.apply(sections());
+        let mut view = FilteredSections::new(registry);
+
+        view.update(
+            &sections,
+            "wasm",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(names(&view), [("Test Section", vec!["Rust Project"])]);
+        view.update(
+            &sections,
+            "-wasm",
+            &TagSelection::default(),
+            ResultOrder::Source,
+        );
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn selected_tags_narrow_matches_and_facets_follow_the_query() {
+        let sections = sections();
+        let mut view = FilteredSections::default();
+        let mut tags = TagSelection::default();
+        tags.toggle("python");
+
+        view.update(&sections, "", &tags, ResultOrder::Source);
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+        let facets = |view: &FilteredSections| -> Vec<(String, usize)> {
+            view.facets()
+                .iter()
+                .map(|tag| (tag.label.clone(), tag.count))
+                .collect()
+        };
+        // 件数は選択したタグではなく検索語で絞った結果から数える
+        assert_eq!(
+            facets(&view),
+            [
+                ("python".to_string(), 2),
+                ("bash".to_string(), 1),
+                ("rust".to_string(), 1),
+                ("wasm".to_string(), 1),
+            ]
+        );
+
+        view.update(&sections, "shell", &tags, ResultOrder::Source);
+        assert_eq!(names(&view), [("Other", vec!["Scripts"])]);
+        assert_eq!(
+            facets(&view),
+            [("bash".to_string(), 1), ("python".to_string(), 1)]
+        );
+
+        tags.toggle("rust");
+        assert!(
+            view.update(&sections, "", &tags, ResultOrder::Source),
+            "tags changed"
+        );
+        assert!(names(&view).is_empty(), "no item has both python and rust");
+        tags.mode = TagMatch::Any;
+        view.update(&sections, "", &tags, ResultOrder::Source);
+        assert_eq!(
+            names(&view),
+            [
+                ("Test Section", vec!["Rust Project", "Python Project"]),
+                ("Other", vec!["Scripts"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn recomputes_only_when_data_or_query_changes() {
+        let sections = sections();
+        let mut view = FilteredSections::default();
+        assert!(view.update(
+            &sections,
+            "rust",
+            &TagSelection::default(),
+            ResultOrder::Source
+        ));
+        assert!(
+            !view.update(
+                &sections,
+                "rust",
+                &TagSelection::default(),
+                ResultOrder::Source
+            ),
+            "same snapshot and query"
+        );
+        assert!(
+            view.update(
+                &sections,
+                "wasm",
+                &TagSelection::default(),
+                ResultOrder::Source
+            ),
+            "query changed"
+        );
+        assert!(
+            view.update(
+                &sections,
+                "wasm",
+                &TagSelection::default(),
+                ResultOrder::Relevance
+            ),
+            "order changed"
+        );
+
+        // 内容が同じでも別のスナップショットなら作り直す
+        let reloaded: Arc<[RepoSection]> = Arc::from(sections.to_vec());
+        assert!(view.update(
+            &reloaded,
+            "wasm",
+            &TagSelection::default(),
+            ResultOrder::Relevance
+        ));
+        assert!(!view.update(
+            &reloaded,
+            "wasm",
+            &TagSelection::default(),
+            ResultOrder::Relevance
+        ));
+    }
+}
@@ -0,0 +1,72 @@
+//! Change detection for local portfolio files (native only).
+//!
+//! Files are polled instead of relying on OS notifications so that editors
+//! which replace the file on save are handled the same way as in-place writes.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How often watched files are checked for changes.
+pub(super) const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Identifies a file revision by its modification time and length.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn read(path: &std::path::Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Remembers the last seen revision of a file and reports when it changes.
+pub(super) struct FileWatcher {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+}
+
+impl FileWatcher {
+    pub(super) fn new(path: PathBuf) -> Self {
+        let stamp = FileStamp::read(&path);
+        Self { path, stamp }
+    }
+
+    /// Returns true when the file was modified, created or removed since the last poll.
+    pub(super) fn poll_changed(&mut self) -> bool {
+        let stamp = FileStamp::read(&self.path);
+        if stamp == self.stamp {
+            return false;
+        }
+        self.stamp = stamp;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poll_reports_each_change_once() {
+        let path =
+            std::env::temp_dir().join(format!("map_panels_watch_{}.yaml", std::process::id()));
+        std::fs::write(&path, "[]").expect("temp file should be writable");
+
+        let mut watcher = FileWatcher::new(path.clone());
+        assert!(!watcher.poll_changed(), "unchanged file should not report");
+
+        std::fs::write(&path, "- name: Changed\n  items: []\n").expect("rewrite should work");
+        assert!(watcher.poll_changed(), "rewritten file should report");
+        assert!(!watcher.poll_changed(), "change should be reported once");
+
+        std::fs::remove_file(&path).expect("temp file should be removable");
+        assert!(watcher.poll_changed(), "removal should report");
+    }
+}
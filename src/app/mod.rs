@@ -1,40 +1,73 @@
+#[cfg(feature = "bench")]
+pub mod bench;
 mod components;
+mod config;
 mod data;
 mod fonts;
 mod layout;
+mod settings;
+mod textures;
 pub mod theme;
 
-use components::{AttributionFooter, FeaturedSection, NavigationBar, RepoCarousel, SettingsAction};
-use data::{load_featured_repo, FeaturedRepo, PortfolioLoadState, PortfolioLoader, RepoSection};
+use std::sync::Arc;
+use std::time::Duration;
+
+use components::{
+    ActiveFilters, AttributionFooter, DisplaySettingsWindow, FeaturedSection, NavigationBar,
+    ParseDiagnostics, RepoCarousel, SettingsAction, SkippedItemsNotice, TagFacetPanel,
+};
+use config::AppConfig;
+use data::{
+    describe_age, load_featured_repo, FeaturedRepo, FilteredSections, PortfolioCache,
+    PortfolioError, PortfolioErrorKind, PortfolioLoadState, PortfolioLoader, RepoSection,
+    SearchHistory, TagSelection,
+};
 use egui_extras::install_image_loaders;
 use fonts::install_fonts;
 use layout::ResponsiveLayout;
-use theme::background;
+use settings::DisplaySettings;
+use textures::TextureCache;
+use theme::{background, text};
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct TemplateApp {
-    featured: FeaturedRepo,
-    sections: Vec<RepoSection>,
+    /// Repositories rotated through in the hero section.
+    featured_repos: Vec<FeaturedRepo>,
+    sections: Arc<[RepoSection]>,
     search_query: String,
+    search_history: SearchHistory,
+    /// Badges picked in the facet panel, combined with `search_query`.
+    tag_selection: TagSelection,
+    /// Last good responses of URL sources, used offline and for revalidation.
+    portfolio_cache: PortfolioCache,
+    display_settings: DisplaySettings,
     #[serde(skip)]
     settings_menu_open: bool,
     #[serde(skip)]
+    display_settings_open: bool,
+    /// Whether the tag facet drawer is shown on narrow screens.
+    #[serde(skip)]
+    facet_drawer_open: bool,
+    /// Loader revision that `sections` was last copied from.
+    #[serde(skip)]
+    sections_revision: u64,
+    #[serde(skip)]
+    config: AppConfig,
+    #[serde(skip)]
     portfolio_loader: PortfolioLoader,
+    /// `sections` narrowed down by `search_query`, recomputed only when an input changes.
+    #[serde(skip)]
+    filtered_sections: FilteredSections,
+    /// Uploaded hero and card images, shared by every component that draws one.
+    #[serde(skip)]
+    textures: TextureCache,
 }
 
 impl Default for TemplateApp {
     fn default() -> Self {
-        let featured = load_featured_repo();
-
-        Self {
-            featured,
-            sections: Vec::new(),
-            search_query: String::new(),
-            settings_menu_open: false,
-            portfolio_loader: PortfolioLoader::new(),
-        }
+        TemplateApp::with_config(AppConfig::default())
     }
 }
 
@@ -47,19 +80,52 @@ impl TemplateApp {
         install_image_loaders(&cc.egui_ctx);
         cc.egui_ctx.set_visuals(egui::Visuals::dark());
 
+        let config = AppConfig::resolve(cc);
+
         // Debug builds skip persistence to make data edits reflect immediately.
         if cfg!(debug_assertions) {
-            return TemplateApp::default();
+            return TemplateApp::with_config(config);
         }
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
-        } else {
-            Default::default()
+        let mut app: TemplateApp = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        app.portfolio_loader =
+            PortfolioLoader::new(config.sources.clone(), app.portfolio_cache.clone());
+        // 前回保存したデータも今の設定のタグでそろえ直す
+        app.sections = config.tags.apply(std::mem::take(&mut app.sections));
+        app.tag_selection.resolve(&config.tags);
+        app.filtered_sections = FilteredSections::new(config.tags.clone());
+        app.config = config;
+        app
+    }
+
+    fn with_config(config: AppConfig) -> Self {
+        Self {
+            featured_repos: vec![load_featured_repo()],
+            sections: Arc::default(),
+            search_query: String::new(),
+            search_history: SearchHistory::default(),
+            tag_selection: TagSelection::default(),
+            portfolio_cache: PortfolioCache::default(),
+            display_settings: DisplaySettings::default(),
+            settings_menu_open: false,
+            display_settings_open: false,
+            facet_drawer_open: false,
+            sections_revision: 0,
+            portfolio_loader: PortfolioLoader::new(
+                config.sources.clone(),
+                PortfolioCache::default(),
+            ),
+            filtered_sections: FilteredSections::new(config.tags.clone()),
+            config,
+            textures: TextureCache::default(),
         }
     }
+
     fn handle_settings_action(
         &mut self,
         action: SettingsAction,
@@ -68,11 +134,14 @@ impl TemplateApp {
     ) {
         match action {
             SettingsAction::RefreshRepoData => {
-                self.portfolio_loader = PortfolioLoader::new();
-                self.portfolio_loader.start_loading(ctx);
+                // 取得中の更新があっても打ち切り、最新の更新だけを反映する
+                self.portfolio_loader.refresh(ctx);
+            }
+            SettingsAction::OpenDisplaySettings => {
+                self.display_settings_open = true;
             }
             SettingsAction::ClearCache => {
-                let replacement = TemplateApp::default();
+                let replacement = TemplateApp::with_config(self.config.clone());
                 if let Some(storage) = frame.storage_mut() {
                     eframe::set_value(storage, eframe::APP_KEY, &replacement);
                     storage.flush();
@@ -82,27 +151,223 @@ impl TemplateApp {
             }
         }
     }
+
+    /// Renders every section that still has items after applying the search query.
+    fn show_sections(&mut self, ui: &mut egui::Ui, layout: ResponsiveLayout) {
+        let changed = self.filtered_sections.update(
+            &self.sections,
+            &self.search_query,
+            &self.tag_selection,
+            self.display_settings.result_order,
+        );
+        if changed {
+            // 検索欄の下のエラー表示は描画済みなので、次のフレームで反映する
+            ui.ctx().request_repaint();
+        }
+        let mut clicked_tag = None;
+        for section in self.filtered_sections.iter() {
+            if let Some(tag) =
+                RepoCarousel::new(section, &self.config.tags, &mut self.textures, layout).show(ui)
+            {
+                clicked_tag = Some(tag);
+            }
+        }
+        if let Some(tag) = clicked_tag {
+            self.tag_selection.add_badge(&tag);
+        }
+    }
+
+    /// Tag facets as a fixed panel on the left.
+    fn facet_sidebar(&mut self, ctx: &egui::Context) {
+        egui::SidePanel::left("tag-facets")
+            .resizable(false)
+            .exact_width(220.0)
+            .frame(
+                egui::Frame::default()
+                    .fill(background::NAV_BAR)
+                    .inner_margin(egui::Margin::symmetric(14, 16)),
+            )
+            .show(ctx, |ui| {
+                TagFacetPanel::new(self.filtered_sections.facets(), &mut self.tag_selection)
+                    .show(ui);
+            });
+    }
+
+    /// Tag facets sliding over the content from the left while the drawer is open.
+    fn facet_drawer(&mut self, ctx: &egui::Context) {
+        if !self.facet_drawer_open {
+            return;
+        }
+        let screen = ctx.content_rect();
+        egui::Area::new(egui::Id::new("tag-facet-drawer"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(screen.left_top())
+            .show(ctx, |ui| {
+                egui::Frame::default()
+                    .fill(background::NAV_BAR)
+                    .inner_margin(egui::Margin::symmetric(14, 16))
+                    .show(ui, |ui| {
+                        ui.set_width((screen.width() * 0.8).min(280.0));
+                        ui.set_min_height(screen.height() - 32.0);
+                        if ui.button("✕ 閉じる").clicked() {
+                            self.facet_drawer_open = false;
+                        }
+                        TagFacetPanel::new(
+                            self.filtered_sections.facets(),
+                            &mut self.tag_selection,
+                        )
+                        .show(ui);
+                    });
+            });
+    }
+
+    /// Button opening the facet drawer, with how many tags are selected.
+    fn facet_drawer_toggle(&mut self, ui: &mut egui::Ui) {
+        let label = match self.tag_selection.len() {
+            0 => "🏷 タグで絞り込み".to_string(),
+            selected => format!("🏷 タグで絞り込み（{selected} 件選択中）"),
+        };
+        if ui.button(label).clicked() {
+            self.facet_drawer_open = !self.facet_drawer_open;
+        }
+    }
+
+    /// Error banner with a manual retry button and the automatic retry countdown.
+    fn error_banner(&self, ui: &mut egui::Ui, error: &PortfolioError, attempt: u32) {
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(egui::Color32::RED, format!("Error: {}", error.summary()))
+                .on_hover_text(error.to_string());
+            if ui.button("再試行").clicked() {
+                self.portfolio_loader.retry_now(ui.ctx());
+            }
+        });
+        let status = match (self.portfolio_loader.time_until_retry(), &error.kind) {
+            (Some(wait), _) => {
+                // カウントダウン表示を毎秒更新する
+                ui.ctx().request_repaint_after(Duration::from_secs(1));
+                format!(
+                    "{attempt}回目の取得に失敗しました。{}秒後に自動で再試行します",
+                    wait.as_secs_f32().ceil()
+                )
+            }
+            (None, PortfolioErrorKind::Parse(_) | PortfolioErrorKind::InvalidUtf8) => {
+                "データの内容を修正してから再試行してください".to_owned()
+            }
+            (None, PortfolioErrorKind::Http { status, .. }) if *status < 500 => {
+                "取得元の設定を確認してください".to_owned()
+            }
+            (None, _) => format!("{attempt}回試行しましたが取得できませんでした"),
+        };
+        ui.label(
+            egui::RichText::new(format!("{status}（{}）", error.source))
+                .small()
+                .color(text::SECONDARY),
+        );
+    }
+
+    /// Shows how old the cached portfolio is, and warns when it replaced a failed fetch.
+    fn cache_status(&self, ui: &mut egui::Ui) {
+        let Some(age) = self.portfolio_loader.cache_age() else {
+            return;
+        };
+        let age = describe_age(age);
+        let fallback_errors = self.portfolio_loader.fallback_errors();
+        if fallback_errors.is_empty() {
+            ui.label(
+                egui::RichText::new(format!("データ取得: {age}"))
+                    .small()
+                    .color(text::SECONDARY),
+            );
+        } else {
+            ui.label(
+                egui::RichText::new(format!(
+                    "⚠ オフライン: {age}に取得したキャッシュを表示しています"
+                ))
+                .color(text::WARNING),
+            )
+            .on_hover_text(
+                fallback_errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+    }
+}
+
+/// Notice shown while a refresh requested from the settings menu is in flight.
+fn refreshing_indicator(ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.add(egui::Spinner::new().size(14.0).color(text::ACCENT));
+        ui.label(egui::RichText::new("リポジトリデータを更新しています…").color(text::ACCENT));
+    });
+}
+
+/// Subtle notice shown above stale sections while loading or retrying in the background.
+fn updating_indicator(ui: &mut egui::Ui, attempt: u32) {
+    let label = if attempt > 1 {
+        format!("再試行中（{attempt}回目）…")
+    } else {
+        "更新中…".to_owned()
+    };
+    ui.horizontal(|ui| {
+        ui.add(egui::Spinner::new().size(12.0).color(text::SECONDARY));
+        ui.label(egui::RichText::new(label).small().color(text::SECONDARY));
+    });
 }
 
 impl eframe::App for TemplateApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.portfolio_cache = self.portfolio_loader.cache();
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        // URLからポートフォリオデータの取得を開始
+        // 設定されたソースからポートフォリオデータの取得を開始
         self.portfolio_loader.start_loading(ctx);
 
-        // ロード完了時にデータを更新
-        if let PortfolioLoadState::Loaded(sections) = self.portfolio_loader.state() {
-            self.sections = sections;
+        // 自動更新はウィンドウが最小化されている間だけ止める
+        // （Web 版は非表示のタブでフレームが進まないため自然に止まる）。
+        // eframe は他のウィンドウに隠れたことを通知しないうえ、壁掛けの画面は
+        // フォーカスがないまま表示され続けるので、フォーカスでは止めない
+        let minimized = ctx.input(|i| i.viewport().minimized.unwrap_or(false));
+        if let (Some(interval), false) = (self.display_settings.auto_refresh.duration(), minimized)
+        {
+            self.portfolio_loader.auto_refresh(ctx, interval);
+        }
+
+        // 読み込んだ内容が変わったときだけデータを差し替える
+        let revision = self.portfolio_loader.revision();
+        if revision != self.sections_revision {
+            if let PortfolioLoadState::Loaded(sections) = self.portfolio_loader.state() {
+                // 別名のタグを正規の名前にそろえてから索引や件数に使う
+                self.sections = self.config.tags.apply(sections);
+                // ソースに注目リポジトリがなければ同梱のものを表示する
+                let featured = self.portfolio_loader.featured();
+                self.featured_repos = if featured.is_empty() {
+                    vec![load_featured_repo()]
+                } else {
+                    featured
+                };
+                self.sections_revision = revision;
+            }
         }
 
         // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
+        // タグの絞り込みは広い画面では左側に常に表示し、狭い画面ではドロワーにする
+        let facets_in_drawer =
+            ResponsiveLayout::from_width(ctx.content_rect().width()).is_compact();
+        if facets_in_drawer {
+            self.facet_drawer(ctx);
+        } else {
+            self.facet_sidebar(ctx);
+        }
+
         egui::CentralPanel::default()
             .frame(
                 egui::Frame::default()
@@ -118,6 +383,9 @@ impl eframe::App for TemplateApp {
                         let layout = ResponsiveLayout::from_width(ui.available_width());
                         let action = NavigationBar::new(
                             &mut self.search_query,
+                            &mut self.search_history,
+                            self.filtered_sections.suggestions(),
+                            self.filtered_sections.query_error(),
                             &mut self.settings_menu_open,
                             layout,
                         )
@@ -125,32 +393,57 @@ impl eframe::App for TemplateApp {
                         if let Some(action) = action {
                             self.handle_settings_action(action, ctx, frame);
                         }
-                        FeaturedSection::new(&self.featured, layout).show(ui);
+                        ActiveFilters::new(
+                            &mut self.tag_selection,
+                            self.filtered_sections.facets(),
+                            &self.config.tags,
+                        )
+                        .show(ui);
+                        if let Some(tag) = FeaturedSection::new(
+                            &self.featured_repos,
+                            &self.config.tags,
+                            &mut self.textures,
+                            layout,
+                        )
+                        .show(ui)
+                        {
+                            self.tag_selection.add_badge(&tag);
+                        }
+                        if facets_in_drawer {
+                            self.facet_drawer_toggle(ui);
+                        }
                         ui.separator();
 
                         // ポートフォリオの状態に応じて表示を変更
                         match self.portfolio_loader.state() {
-                            PortfolioLoadState::Loading | PortfolioLoadState::NotStarted => {
+                            PortfolioLoadState::NotStarted => {
                                 ui.spinner();
                                 ui.label("Loading portfolio...");
                             }
-                            PortfolioLoadState::Error(err) => {
-                                ui.colored_label(egui::Color32::RED, format!("Error: {err}"));
-                                // エラー時はフォールバックデータを表示
-                                for section in &self.sections {
-                                    let filtered = section.filter_by_query(&self.search_query);
-                                    if !filtered.items.is_empty() {
-                                        RepoCarousel::new(&filtered, layout).show(ui);
-                                    }
+                            PortfolioLoadState::Loading { attempt } => {
+                                if self.sections.is_empty() {
+                                    ui.spinner();
+                                    ui.label("Loading portfolio...");
+                                } else {
+                                    // 取得中も前回のデータを表示し、成功時にのみ差し替える
+                                    updating_indicator(ui, attempt);
+                                    self.show_sections(ui, layout);
                                 }
                             }
+                            PortfolioLoadState::Refreshing => {
+                                refreshing_indicator(ui);
+                                self.show_sections(ui, layout);
+                            }
+                            PortfolioLoadState::Error { error, attempt, .. } => {
+                                self.error_banner(ui, &error, attempt);
+                                ParseDiagnostics::new(&error).show(ui);
+                                // エラー時はフォールバックデータを表示
+                                self.show_sections(ui, layout);
+                            }
                             PortfolioLoadState::Loaded(_) => {
-                                for section in &self.sections {
-                                    let filtered = section.filter_by_query(&self.search_query);
-                                    if !filtered.items.is_empty() {
-                                        RepoCarousel::new(&filtered, layout).show(ui);
-                                    }
-                                }
+                                self.cache_status(ui);
+                                SkippedItemsNotice::new(&self.portfolio_loader.warnings()).show(ui);
+                                self.show_sections(ui, layout);
                             }
                         }
 
@@ -158,6 +451,12 @@ impl eframe::App for TemplateApp {
                         AttributionFooter::new().show(ui);
                     });
             });
+
+        DisplaySettingsWindow::new(&mut self.display_settings_open, &mut self.display_settings)
+            .show(ctx);
+
+        // このフレームで使われなかった画像を一定期間後に解放する
+        self.textures.end_frame();
     }
 }
 
@@ -170,7 +469,7 @@ mod tests {
     fn default_ui_data_contains_featured() {
         let app = TemplateApp::default();
 
-        assert!(!app.featured.name.is_empty());
+        assert!(!app.featured_repos[0].name.is_empty());
         // sections は URL から非同期でロードされるため、初期状態では空
         assert!(app.sections.is_empty(), "セクションは初期状態で空");
     }
@@ -0,0 +1,98 @@
+//! User preferences edited from the "表示設定" window and persisted with the app state.
+
+use std::time::Duration;
+
+/// How often the portfolio is reloaded in the background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) enum RefreshInterval {
+    #[default]
+    Off,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+}
+
+impl RefreshInterval {
+    pub(crate) const ALL: [RefreshInterval; 5] = [
+        RefreshInterval::Off,
+        RefreshInterval::FiveMinutes,
+        RefreshInterval::FifteenMinutes,
+        RefreshInterval::ThirtyMinutes,
+        RefreshInterval::OneHour,
+    ];
+
+    /// Time between refreshes, or `None` when automatic refresh is off.
+    pub(crate) fn duration(self) -> Option<Duration> {
+        let minutes = match self {
+            RefreshInterval::Off => return None,
+            RefreshInterval::FiveMinutes => 5,
+            RefreshInterval::FifteenMinutes => 15,
+            RefreshInterval::ThirtyMinutes => 30,
+            RefreshInterval::OneHour => 60,
+        };
+        Some(Duration::from_secs(minutes * 60))
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            RefreshInterval::Off => "自動更新しない",
+            RefreshInterval::FiveMinutes => "5分ごと",
+            RefreshInterval::FifteenMinutes => "15分ごと",
+            RefreshInterval::ThirtyMinutes => "30分ごと",
+            RefreshInterval::OneHour => "1時間ごと",
+        }
+    }
+}
+
+/// Order of the repositories shown while searching.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub(crate) enum ResultOrder {
+    /// As written in the portfolio.
+    #[default]
+    Source,
+    /// Best matches first, both within and across sections.
+    Relevance,
+}
+
+impl ResultOrder {
+    pub(crate) const ALL: [ResultOrder; 2] = [ResultOrder::Source, ResultOrder::Relevance];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ResultOrder::Source => "データの順",
+            ResultOrder::Relevance => "関連度順",
+        }
+    }
+}
+
+/// Preferences shown in the display settings window.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub(crate) struct DisplaySettings {
+    pub(crate) auto_refresh: RefreshInterval,
+    pub(crate) result_order: ResultOrder,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_refresh_is_off_by_default() {
+        assert_eq!(DisplaySettings::default().auto_refresh.duration(), None);
+    }
+
+    #[test]
+    fn intervals_are_ordered_and_labelled() {
+        let durations: Vec<u64> = RefreshInterval::ALL
+            .iter()
+            .filter_map(|interval| interval.duration())
+            .map(|duration| duration.as_secs() / 60)
+            .collect();
+        assert_eq!(durations, [5, 15, 30, 60]);
+        for interval in RefreshInterval::ALL {
+            assert!(!interval.label().is_empty());
+        }
+    }
+}
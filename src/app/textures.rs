@@ -0,0 +1,264 @@
+//! Texture cache shared by the components that draw images.
+//!
+//! Images are decoded by the egui image loaders, uploaded once, and the
+//! resulting [`egui::TextureHandle`] is kept here keyed by its URI so later
+//! frames reuse it. Textures that are not drawn for a while are dropped, which
+//! frees them on the GPU, and are loaded again if they come back into view.
+//! Images that fail to load are tried again with the same backoff as the
+//! portfolio, so a network hiccup does not leave a card without its image.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::data::{jitter, RetryPolicy};
+
+/// Frames a texture may go unused before it is evicted.
+const DEFAULT_KEEP_FRAMES: u64 = 600;
+
+/// Outcome of looking up a texture.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum TextureState {
+    /// Still downloading or decoding; the loader repaints when it is done.
+    Pending,
+    Ready(egui::load::SizedTexture),
+    /// The image could not be loaded. Retried after a backoff while still drawn.
+    Failed,
+}
+
+enum Slot {
+    Ready(egui::TextureHandle),
+    /// Failed `attempts` times in a row; loaded again once egui's time reaches `retry_at`.
+    Failed {
+        attempts: u32,
+        retry_at: Duration,
+    },
+}
+
+struct Entry {
+    slot: Slot,
+    last_used: u64,
+}
+
+/// Uploaded textures keyed by image URI.
+pub(crate) struct TextureCache {
+    entries: HashMap<String, Entry>,
+    keep_frames: u64,
+    frame: u64,
+    retry: RetryPolicy,
+}
+
+impl Default for TextureCache {
+    fn default() -> Self {
+        TextureCache::with_keep_frames(DEFAULT_KEEP_FRAMES)
+    }
+}
+
+impl TextureCache {
+    pub(crate) fn with_keep_frames(keep_frames: u64) -> Self {
+        Self {
+            entries: HashMap::new(),
+            keep_frames,
+            frame: 0,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Texture for `uri`, loading it through the installed image loaders on first use.
+    pub(crate) fn texture(&mut self, ctx: &egui::Context, uri: &str) -> TextureState {
+        let now = Duration::from_secs_f64(ctx.input(|input| input.time));
+        let mut failures = 0;
+        if let Some(entry) = self.entries.get_mut(uri) {
+            entry.last_used = self.frame;
+            match &entry.slot {
+                Slot::Ready(handle) => {
+                    return TextureState::Ready(egui::load::SizedTexture::from_handle(handle))
+                }
+                Slot::Failed { retry_at, .. } if now < *retry_at => return TextureState::Failed,
+                Slot::Failed { attempts, .. } => failures = *attempts,
+            }
+        }
+
+        let slot = match ctx.try_load_image(uri, egui::SizeHint::default()) {
+            Ok(egui::load::ImagePoll::Pending { .. }) => return TextureState::Pending,
+            Ok(egui::load::ImagePoll::Ready { image }) => {
+                let handle = ctx.load_texture(uri, image, egui::TextureOptions::LINEAR);
+                Slot::Ready(handle)
+            }
+            Err(err) => {
+                let attempts = failures + 1;
+                let delay = self.retry.delay(attempts, jitter());
+                log::warn!("Failed to load image {uri} (attempt {attempts}): {err}");
+                // 表示中のカードが待ち時間の後に読み込み直せるよう、その時点で再描画する
+                ctx.request_repaint_after(delay);
+                Slot::Failed {
+                    attempts,
+                    retry_at: now + delay,
+                }
+            }
+        };
+        // The handle now owns the pixels, so the loaders' copies are no longer needed.
+        ctx.forget_image(uri);
+
+        let state = match &slot {
+            Slot::Ready(handle) => {
+                TextureState::Ready(egui::load::SizedTexture::from_handle(handle))
+            }
+            Slot::Failed { .. } => TextureState::Failed,
+        };
+        self.entries.insert(
+            uri.to_owned(),
+            Entry {
+                slot,
+                last_used: self.frame,
+            },
+        );
+        state
+    }
+
+    /// Like [`TextureCache::texture`] for an image bundled into the binary.
+    pub(crate) fn bundled_texture(
+        &mut self,
+        ctx: &egui::Context,
+        uri: &'static str,
+        bytes: &'static [u8],
+    ) -> TextureState {
+        // 失敗した画像のバイト列も忘れているので、再試行に備えて登録し直す
+        if !matches!(
+            self.entries.get(uri),
+            Some(Entry {
+                slot: Slot::Ready(_),
+                ..
+            })
+        ) {
+            ctx.include_bytes(uri, bytes);
+        }
+        self.texture(ctx, uri)
+    }
+
+    /// Advances the frame counter and evicts textures that went unused for too long.
+    ///
+    /// Call once at the end of every frame.
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+        let (frame, keep_frames) = (self.frame, self.keep_frames);
+        self.entries
+            .retain(|_, entry| frame - entry.last_used <= keep_frames);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGO_URI: &str = "bytes://test-logo.webp";
+    const LOGO_BYTES: &[u8] = include_bytes!("../../assets/logo.webp");
+
+    fn context() -> egui::Context {
+        let ctx = egui::Context::default();
+        egui_extras::install_image_loaders(&ctx);
+        ctx
+    }
+
+    /// Polls until the background decoder finishes.
+    fn wait_ready(cache: &mut TextureCache, ctx: &egui::Context) -> egui::load::SizedTexture {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            match cache.bundled_texture(ctx, LOGO_URI, LOGO_BYTES) {
+                TextureState::Ready(texture) => return texture,
+                TextureState::Failed => panic!("bundled logo should decode"),
+                TextureState::Pending => {}
+            }
+            assert!(std::time::Instant::now() < deadline, "decoding timed out");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn texture_is_uploaded_once_and_reused() {
+        let ctx = context();
+        let mut cache = TextureCache::default();
+        let first = wait_ready(&mut cache, &ctx);
+        cache.end_frame();
+        let second = wait_ready(&mut cache, &ctx);
+        assert_eq!(first.id, second.id, "the same texture should be reused");
+        assert!(first.size.x > 0.0 && first.size.y > 0.0);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn unused_textures_are_evicted() {
+        let ctx = context();
+        let mut cache = TextureCache::with_keep_frames(2);
+        wait_ready(&mut cache, &ctx);
+
+        cache.end_frame();
+        cache.end_frame();
+        assert_eq!(cache.len(), 1, "still within the keep window");
+        cache.end_frame();
+        assert_eq!(cache.len(), 0, "unused for longer than the keep window");
+
+        // 再び使われたら読み込み直す
+        wait_ready(&mut cache, &ctx);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn failed_images_are_remembered() {
+        let ctx = context();
+        let mut cache = TextureCache::default();
+        let state = cache.bundled_texture(&ctx, "bytes://broken.webp", b"not an image");
+        let state = match state {
+            TextureState::Pending => {
+                let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+                loop {
+                    match cache.texture(&ctx, "bytes://broken.webp") {
+                        TextureState::Pending => {
+                            assert!(std::time::Instant::now() < deadline, "decoding timed out");
+                            std::thread::sleep(std::time::Duration::from_millis(10));
+                        }
+                        state => break state,
+                    }
+                }
+            }
+            state => state,
+        };
+        assert!(matches!(state, TextureState::Failed));
+        assert!(matches!(
+            cache.texture(&ctx, "bytes://broken.webp"),
+            TextureState::Failed
+        ));
+    }
+
+    #[test]
+    fn failed_images_are_retried_after_the_backoff() {
+        let ctx = context();
+        let mut cache = TextureCache {
+            retry: RetryPolicy {
+                base_delay: Duration::ZERO,
+                ..RetryPolicy::default()
+            },
+            ..TextureCache::default()
+        };
+        let uri = "bytes://late-logo.webp";
+        assert!(matches!(cache.texture(&ctx, uri), TextureState::Failed));
+
+        // 一時的な失敗の後で取得できるようになった画像は、表示中のまま読み込み直す
+        ctx.include_bytes(uri, LOGO_BYTES);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        loop {
+            match cache.texture(&ctx, uri) {
+                TextureState::Ready(_) => break,
+                TextureState::Failed => panic!("the retry should load the image"),
+                TextureState::Pending => {}
+            }
+            assert!(std::time::Instant::now() < deadline, "decoding timed out");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -23,6 +23,9 @@ pub mod text {
     /// Accent text color (blue)
     pub const ACCENT: Color32 = Color32::from_rgb(59, 154, 255);
 
+    /// Warning text color (amber), e.g. for offline notices
+    pub const WARNING: Color32 = Color32::from_rgb(255, 196, 77);
+
     /// Semi-transparent white text
     pub const WHITE_ALPHA_235: Color32 = Color32::from_rgba_premultiplied(235, 235, 235, 235);
 
@@ -54,6 +57,21 @@ pub mod background {
 
     /// Tag chip background
     pub const TAG_CHIP: Color32 = Color32::from_rgba_premultiplied(100, 100, 100, 100);
+
+    /// Background of a tag chip standing for an active filter
+    pub const TAG_CHIP_ACTIVE: Color32 = Color32::from_rgb(28, 70, 120);
+
+    /// Code excerpt background in diagnostics
+    pub const CODE: Color32 = Color32::from_rgb(14, 16, 26);
+
+    /// Highlight for the line a diagnostic points at
+    pub const ERROR_LINE: Color32 = Color32::from_rgb(74, 28, 36);
+
+    /// Highlight for the exact column a diagnostic points at
+    pub const ERROR_MARK: Color32 = Color32::from_rgb(170, 40, 55);
+
+    /// Highlight for text matching the search query
+    pub const SEARCH_MATCH: Color32 = Color32::from_rgb(122, 92, 24);
 }
 
 /// Stroke/border colors
@@ -91,6 +109,7 @@ mod tests {
         assert!(text::MUTED.a() > 0, "MUTED text should be visible");
         assert!(text::DARK.a() > 0, "DARK text should be visible");
         assert!(text::ACCENT.a() > 0, "ACCENT text should be visible");
+        assert!(text::WARNING.a() > 0, "WARNING text should be visible");
         assert!(
             text::WHITE_ALPHA_235.a() > 0,
             "WHITE_ALPHA_235 text should be visible"
@@ -2,3 +2,7 @@
 
 mod app;
 pub use app::TemplateApp;
+
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub use app::bench;